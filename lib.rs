@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
 
 declare_id!("AommrqkSe6eq2xqV8VoXKMd3zpY6sEoHHgFNbCjXBLeX");
 
@@ -6,6 +10,119 @@ declare_id!("AommrqkSe6eq2xqV8VoXKMd3zpY6sEoHHgFNbCjXBLeX");
 const MAX_PING_INTERVAL: i64 = 365 * 24 * 60 * 60; // 1 year
 const MIN_PING_INTERVAL: i64 = 60; // 1 minute
 const MAX_DATA_SIZE: usize = 512; // Maximum encrypted data size in bytes
+const MAX_SWITCHES_PER_OWNER: u32 = 32; // Maximum live switches per owner
+const MAX_BATCH_SIZE: usize = 64; // Maximum switches per batch instruction
+const MAX_SESSION_DURATION: i64 = 30 * 24 * 60 * 60; // 30 days
+const SCHEDULE_TIERS: usize = 4; // Number of interval schedule tiers
+const MAX_CLAIM_ATTEMPTS: u8 = 5; // Failed claims allowed before lockout
+const CLAIM_LOCKOUT: i64 = 24 * 60 * 60; // 1 day claim cooldown
+const MIN_ENTROPY_CHECK_LEN: usize = 16; // Entropy is unreliable below this size
+const MIN_ENTROPY_PERCENT: u64 = 65; // Required share of the maximum entropy for the length
+const MAX_LOG_ENTRIES: usize = 24; // Custody log capacity, sized so read_log fits in return data
+
+// Custody log action codes
+const LOG_OPENED: u8 = 0;
+const LOG_PING: u8 = 1;
+const LOG_SETTINGS: u8 = 2;
+const LOG_SESSION: u8 = 3;
+const LOG_DEACTIVATED: u8 = 4;
+const LOG_EXPIRED: u8 = 5;
+const LOG_CALLBACK: u8 = 6;
+const LOG_REVEALED: u8 = 7;
+const LOG_CLAIMED: u8 = 8;
+const LOG_CLOSED: u8 = 9;
+const LOG_KEEPER_REVEAL: u8 = 10;
+const LOG_PREPAID: u8 = 11;
+const LOG_OWNERSHIP: u8 = 12;
+const LOG_DELEGATES: u8 = 13;
+const LOG_CONFIRMED: u8 = 14;
+const LOG_MAINTENANCE: u8 = 15;
+const MAX_KEEPER_REWARD: u64 = 10_000_000; // 0.01 SOL maximum paid per reveal
+const MAX_GRACE_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+const MAX_BANKED_TIME: i64 = 365 * 24 * 60 * 60; // 1 year of prepaid time
+const MAX_MAINTENANCE_EXTENSION: i64 = 30 * 24 * 60 * 60; // 30 days of maintenance per ping
+const MAX_DELEGATES: usize = 8; // Delegate slots reserved per switch, the safety cap for max_delegates
+const DEFAULT_MAX_DELEGATES: u8 = 5; // Delegate limit applied until the admin changes it
+const MAX_DELEGATE_PINGS: u8 = 3; // Consecutive delegate pings allowed before the owner must ping
+const RAW_STATE_CHUNK: usize = 1020; // Return data cap (1024) minus the Vec length prefix
+const EXPORT_MAGIC: [u8; 4] = *b"DMSX"; // Leading bytes of every state export
+const EXPORT_VERSION: u8 = 1; // Export format version; imports reject any other
+const NONCE_SIZE: usize = 12; // 96-bit AEAD nonce returned by derive_nonce
+const EXPORT_HEADER_SIZE: usize = 4 + 1 + 4; // Magic, version, body length (u32 LE)
+const MAX_SEGMENTS: usize = 4; // Beneficiaries that can each claim a slice of the data
+const DISCLOSURE_STAGES: usize = 3; // Progressive disclosure steps after the reveal
+const MAX_ROLES: usize = 5; // Keys that can hold role permissions on a switch
+const PING_HISTORY_SIZE: usize = 8; // Recent ping timestamps kept for the sliding window
+const MAX_PROOF_LENGTH: usize = 32; // Merkle proof depth, enough for 2^32 beneficiaries
+const MAX_CLOCK_TOLERANCE: i64 = 5 * 60; // 5 minutes of allowed validator clock skew
+const MAX_REVEAL_DELAY: i64 = 30 * 24 * 60 * 60; // 30 days of cooling-off after expiry
+const MAX_CONFIRM_INTERVAL: i64 = 30 * 24 * 60 * 60; // 30 days between missing and dead
+const MAX_REWARD_DECAY_WINDOW: i64 = 30 * 24 * 60 * 60; // 30 days for the escrow to decay to zero
+const LIVENESS_FULL_CONFIDENCE_PINGS: u64 = 10; // Pings needed before the liveness score can reach 100
+const MAX_CREATION_FEE: u64 = 1_000_000_000; // 1 SOL maximum switch creation fee
+const MAX_EXPIRING_RESULTS: usize = 31; // Keys that fit in return data alongside the Vec prefix
+const MAX_CLOSE_COOLDOWN: i64 = 30 * 24 * 60 * 60; // 30 days between deactivation and closure
+const LIGHT_PING_EXTENSION: i64 = 60 * 60; // 1 hour granted by light_ping, capped at the interval
+const SENSITIVITY_LEVELS: usize = 4; // Sensitivity 0 (routine) through 3 (top secret)
+const LATE_PING_TOLERANCE: i64 = 10 * 60; // 10 minutes past the reveal time a late ping may still land
+const CANCEL_WINDOW: i64 = 60 * 60; // 1 hour after creation during which an unpinged switch can be cancelled
+
+// Status byte bits, see DeadManSwitch::STATUS_OFFSET
+const STATUS_ACTIVE: u8 = 1 << 0;
+const STATUS_REVEALED: u8 = 1 << 1;
+const STATUS_FROZEN: u8 = 1 << 3; // Data hash locked in at settlement (bit 2 is reserved for pausing)
+
+// Per-switch bits returned by batch_expiration_compact (bit 2 is reserved for pausing)
+const COMPACT_EXPIRED: u8 = 1 << 0;
+const COMPACT_ACTIVE: u8 = 1 << 1;
+
+// Switch modes
+const MODE_DEAD_MAN: u8 = 0; // Expires unless pinged (classic proof of life)
+const MODE_KEEPALIVE: u8 = 1; // Stays alive until armed, then expires after the interval
+
+// Liveness phases; the confirmation interval separates missing from dead
+const PHASE_ALIVE: u8 = 0; // Within the ping interval
+const PHASE_MISSING: u8 = 1; // Interval lapsed, still cancellable by a ping
+const PHASE_DEAD: u8 = 2; // Silence confirmed, settles once any reveal delay has passed
+
+// Role bits returned by get_my_role; a key with no role gets 0
+const ROLE_OWNER: u8 = 1 << 0;
+const ROLE_DELEGATE: u8 = 1 << 1;
+const ROLE_BENEFICIARY: u8 = 1 << 2; // Primary or segment beneficiary
+const ROLE_WITNESS: u8 = 1 << 3;
+
+// Permission bits a role grant can carry
+const PERM_PING: u8 = 1 << 0; // Ping like a delegate
+const PERM_UPDATE_DATA: u8 = 1 << 1; // Replace the encrypted data
+const ALL_PERMISSIONS: u8 = PERM_PING | PERM_UPDATE_DATA;
+
+// Event verbosity levels
+const LOG_LEVEL_MINIMAL: u8 = 0; // Only state transitions, no routine ping events
+const LOG_LEVEL_NORMAL: u8 = 1; // Every ping emits SwitchPinged
+const LOG_LEVEL_VERBOSE: u8 = 2; // Pings also emit PingDetail
+
+// Expiry actions a switch can declare
+const ON_EXPIRY_BENEFICIARY: u8 = 0; // Beneficiaries claim the data
+const ON_EXPIRY_PUBLIC: u8 = 1; // Anyone can read the data
+const ON_EXPIRY_CALLBACK: u8 = 2; // The callback program is invoked
+const ON_EXPIRY_RELEASE_TOKENS: u8 = 3; // Escrowed tokens are released
+
+// Where the ciphertext lives; off-chain kinds keep only key material on chain
+const STORAGE_INLINE: u8 = 0; // encrypted_data is the ciphertext
+const STORAGE_IPFS: u8 = 1; // storage_ref is an IPFS CID
+const STORAGE_ARWEAVE: u8 = 2; // storage_ref is an Arweave transaction ID
+const STORAGE_REF_SIZE: usize = 64; // Content address capacity in bytes
+
+// Advisory jurisdiction tag: printable ASCII, left-aligned and zero-padded
+const JURISDICTION_SIZE: usize = 8;
+
+// SPL Token program, invoked directly to mint claim receipts
+const TOKEN_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_MINT_LEN: usize = 82; // Packed spl_token::state::Mint
+const TOKEN_ACCOUNT_LEN: usize = 165; // Packed spl_token::state::Account
+const TOKEN_IX_MINT_TO: u8 = 7;
+const TOKEN_IX_FREEZE_ACCOUNT: u8 = 10;
 
 #[program]
 mod dead_mans_switch {
@@ -17,74 +134,408 @@ mod dead_mans_switch {
         id: u64,
         ping_interval: i64,
         encrypted_data: Vec<u8>,
+        close_authority: Option<Pubkey>,
+        warn_threshold: i64,
+        segments: [BeneficiarySegment; MAX_SEGMENTS],
+        on_expiry: u8,
+        storage_kind: u8,
+        storage_ref: [u8; STORAGE_REF_SIZE],
+        max_data_size: u16,
+        log_level: u8,
+        beneficiary_pubkey: Option<[u8; 32]>,
+        jurisdiction: [u8; JURISDICTION_SIZE],
     ) -> Result<()> {
-        // Validate inputs
-        require!(id > 0, ErrorCode::InvalidSwitchId);
+        require_not_paused(&ctx.accounts.config)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let owner = *ctx.accounts.owner.key;
+
+        // Zero interval and warning threshold fall back to the owner's defaults
+        let defaults = ctx.accounts.defaults.as_deref();
+        let ping_interval = if ping_interval == 0 {
+            defaults.ok_or(ErrorCode::DefaultsRequired)?.ping_interval
+        } else {
+            ping_interval
+        };
+        let warn_threshold = match defaults {
+            Some(defaults) if warn_threshold == 0 => defaults.warn_threshold,
+            _ => warn_threshold,
+        };
+
+        let fee = charge_creation_fee(
+            &ctx.accounts.config,
+            ctx.accounts.treasury.as_ref(),
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+        )?;
+        register_switch(&mut ctx.accounts.registry, owner, ctx.bumps.registry)?;
+        reserve_id(&mut ctx.accounts.registry, id)?;
+        ctx.accounts
+            .stats
+            .record_created(switch_rent(DeadManSwitch::SPACE)?)?;
+
+        let switch = &mut ctx.accounts.switch;
+        init_switch(
+            switch,
+            owner,
+            id,
+            ping_interval,
+            &encrypted_data,
+            ctx.bumps.switch,
+            current_time,
+        )?;
+        switch.close_authority = close_authority;
+        if let Some(defaults) = defaults {
+            require!(
+                (0..=MAX_GRACE_PERIOD).contains(&defaults.grace_period),
+                ErrorCode::InvalidGracePeriod
+            );
+            switch.grace_period = defaults.grace_period;
+            switch.encryption_scheme = defaults.encryption_scheme;
+        }
+
         require!(
-            ping_interval >= MIN_PING_INTERVAL && ping_interval <= MAX_PING_INTERVAL,
-            ErrorCode::InvalidInterval
+            warn_threshold >= 0 && warn_threshold < ping_interval,
+            ErrorCode::InvalidWarnThreshold
         );
+        switch.warn_threshold = warn_threshold;
+
+        validate_segments(&segments, switch.data_length)?;
+        switch.segments = segments;
+
         require!(
-            encrypted_data.len() <= MAX_DATA_SIZE,
-            ErrorCode::DataTooLarge
+            on_expiry <= ON_EXPIRY_RELEASE_TOKENS,
+            ErrorCode::InvalidExpiryAction
         );
-        require!(!encrypted_data.is_empty(), ErrorCode::EmptyData);
+        switch.on_expiry = on_expiry;
 
-        let switch = &mut ctx.accounts.switch;
-        let current_time = Clock::get()?.unix_timestamp;
+        validate_storage_ref(storage_kind, &storage_ref)?;
+        switch.storage_kind = storage_kind;
+        switch.storage_ref = storage_ref;
 
-        // Initialize account
-        switch.owner = *ctx.accounts.owner.key;
-        switch.last_ping = current_time;
-        switch.ping_interval = ping_interval;
-        switch.expired = false; // Initialize as active
+        // Only a cap on writes: the account always reserves MAX_DATA_SIZE inline, so its
+        // size and rent don't change. Zero keeps the full capacity.
+        if max_data_size != 0 {
+            require!(
+                max_data_size as usize <= MAX_DATA_SIZE,
+                ErrorCode::InvalidMaxDataSize
+            );
+            require!(switch.data_length <= max_data_size, ErrorCode::DataTooLarge);
+            switch.max_data_size = max_data_size;
+        }
 
-        // Copy encrypted data to fixed array
-        switch.encrypted_data = [0u8; MAX_DATA_SIZE];
-        switch.encrypted_data[..encrypted_data.len()].copy_from_slice(&encrypted_data);
-        switch.data_length = encrypted_data.len() as u16;
+        require!(log_level <= LOG_LEVEL_VERBOSE, ErrorCode::InvalidLogLevel);
+        switch.log_level = log_level;
 
-        switch.created_at = current_time;
-        switch.bump = ctx.bumps.switch;
+        validate_beneficiary_pubkey(&beneficiary_pubkey)?;
+        switch.beneficiary_pubkey = beneficiary_pubkey;
 
-        let expiration_time = current_time
-            .checked_add(ping_interval)
-            .ok_or(ErrorCode::TimeOverflow)?;
+        validate_jurisdiction(&jurisdiction)?;
+        switch.jurisdiction = jurisdiction;
+
+        refresh_cached_expiration(switch, current_time);
+        let expiration_time =
+            expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
 
         emit!(SwitchCreated {
             switch: switch.key(),
-            owner: *ctx.accounts.owner.key,
+            owner,
             switch_id: id,
             ping_interval,
             expiration_time,
             timestamp: current_time,
+            template: None,
+            seq: switch.seq,
+            fee,
         });
 
         Ok(())
     }
 
+    /// Creates a new switch under the owner's next unused ID, returning the ID
+    ///
+    /// IDs assigned here are never reused, even after the switch is closed.
+    pub fn create_switch_auto_id(
+        ctx: Context<CreateSwitchAutoId>,
+        ping_interval: i64,
+        encrypted_data: Vec<u8>,
+    ) -> Result<u64> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let owner = *ctx.accounts.owner.key;
+        let id = ctx.accounts.registry.next_auto_id();
+
+        let fee = charge_creation_fee(
+            &ctx.accounts.config,
+            ctx.accounts.treasury.as_ref(),
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+        )?;
+        register_switch(&mut ctx.accounts.registry, owner, ctx.bumps.registry)?;
+        reserve_id(&mut ctx.accounts.registry, id)?;
+        ctx.accounts
+            .stats
+            .record_created(switch_rent(DeadManSwitch::SPACE)?)?;
+
+        let switch = &mut ctx.accounts.switch;
+        init_switch(
+            switch,
+            owner,
+            id,
+            ping_interval,
+            &encrypted_data,
+            ctx.bumps.switch,
+            current_time,
+        )?;
+
+        refresh_cached_expiration(switch, current_time);
+        let expiration_time =
+            expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+
+        emit!(SwitchCreated {
+            switch: switch.key(),
+            owner,
+            switch_id: id,
+            ping_interval,
+            expiration_time,
+            timestamp: current_time,
+            template: None,
+            seq: switch.seq,
+            fee,
+        });
+
+        Ok(id)
+    }
+
     /// Resets the switch's expiration timer
     pub fn ping(ctx: Context<Ping>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Switches that have opted into strict mode must keep using counters
+        require!(switch.last_counter == 0, ErrorCode::InvalidCounter);
+
+        let activating = is_awaiting_activation(switch);
+        let late_by = late_ping_delay(switch, current_time);
+        let new_expiration = record_ping(switch, ctx.accounts.authority.key(), current_time)?;
+        clear_backup_snapshot(ctx.accounts.backup.as_deref_mut());
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            ctx.accounts.authority.key(),
+            LOG_PING,
+            current_time,
+        )?;
+
+        emit_ping_events(
+            switch,
+            ctx.accounts.authority.key(),
+            new_expiration,
+            activating,
+            late_by,
+            None,
+            current_time,
+        );
+
+        Ok(())
+    }
+
+    /// Resets the timer with a monotonic proof-of-life counter (strict mode)
+    pub fn ping_strict(ctx: Context<Ping>, counter: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Counter must strictly increase, independent of block time
+        require!(counter > switch.last_counter, ErrorCode::InvalidCounter);
+
+        let activating = is_awaiting_activation(switch);
+        let late_by = late_ping_delay(switch, current_time);
+        let new_expiration = record_ping(switch, ctx.accounts.authority.key(), current_time)?;
+        clear_backup_snapshot(ctx.accounts.backup.as_deref_mut());
+        switch.last_counter = counter;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            ctx.accounts.authority.key(),
+            LOG_PING,
+            current_time,
+        )?;
+
+        emit_ping_events(
+            switch,
+            ctx.accounts.authority.key(),
+            new_expiration,
+            activating,
+            late_by,
+            None,
+            current_time,
+        );
+
+        Ok(())
+    }
+
+    /// Resets the timer and records an audit memo for the check-in
+    pub fn ping_with_memo(ctx: Context<Ping>, memo: [u8; 32]) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Switches that have opted into strict mode must keep using counters
+        require!(switch.last_counter == 0, ErrorCode::InvalidCounter);
+        require!(memo != [0u8; 32], ErrorCode::EmptyMemo);
+
+        let activating = is_awaiting_activation(switch);
+        let late_by = late_ping_delay(switch, current_time);
+        let new_expiration = record_ping(switch, ctx.accounts.authority.key(), current_time)?;
+        clear_backup_snapshot(ctx.accounts.backup.as_deref_mut());
+        switch.last_memo = memo;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            ctx.accounts.authority.key(),
+            LOG_PING,
+            current_time,
+        )?;
+
+        emit_ping_events(
+            switch,
+            ctx.accounts.authority.key(),
+            new_expiration,
+            activating,
+            late_by,
+            Some(memo),
+            current_time,
+        );
+
+        Ok(())
+    }
+
+    /// Sets or clears the program invoked when the switch expires
+    pub fn set_callback_program(
+        ctx: Context<SetCallbackProgram>,
+        callback_program: Option<Pubkey>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
         let switch = &mut ctx.accounts.switch;
         let current_time = Clock::get()?.unix_timestamp;
 
-        // Security checks
         require!(!is_expired(switch, current_time), ErrorCode::Expired);
-        require!(current_time >= switch.last_ping, ErrorCode::InvalidTimestamp);
+        if let Some(program) = callback_program {
+            require!(program != crate::ID, ErrorCode::InvalidCallbackProgram);
+        }
 
-        // Update state
-        switch.last_ping = current_time;
-        switch.expired = false; // Reset expiration status
+        switch.callback_program = callback_program;
+        switch.last_actor = *ctx.accounts.owner.key;
 
-        let new_expiration = current_time
-            .checked_add(switch.ping_interval)
-            .ok_or(ErrorCode::TimeOverflow)?;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
 
-        emit!(SwitchPinged {
-            owner: switch.owner,
-            switch_key: switch.key(),
-            next_required_ping: new_expiration,
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Invokes the callback program once the switch has expired (can be called by anyone)
+    pub fn trigger_callback(ctx: Context<TriggerCallback>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require_not_settling(switch)?;
+        require!(
+            switch.on_expiry == ON_EXPIRY_CALLBACK,
+            ErrorCode::WrongExpiryAction
+        );
+        require!(is_revealable(switch, current_time), ErrorCode::NotExpired);
+        require!(
+            !switch.callback_triggered,
+            ErrorCode::CallbackAlreadyTriggered
+        );
+
+        let callback_program = switch
+            .callback_program
+            .ok_or(ErrorCode::NoCallbackProgram)?;
+        require_keys_eq!(
+            ctx.accounts.callback_program.key(),
+            callback_program,
+            ErrorCode::InvalidCallbackProgram
+        );
+
+        // Instruction data: switch key (32 bytes) followed by data length (2 bytes LE)
+        let switch_key = switch.key();
+        let mut data = Vec::with_capacity(32 + 2);
+        data.extend_from_slice(switch_key.as_ref());
+        data.extend_from_slice(&switch.data_length.to_le_bytes());
+
+        let instruction = Instruction {
+            program_id: callback_program,
+            accounts: vec![AccountMeta::new_readonly(switch_key, true)],
+            data,
+        };
+
+        let creator = switch.creator;
+        let id_bytes = switch.switch_id.to_le_bytes();
+        let bump = [switch.bump];
+        let signer_seeds: &[&[u8]] = &[b"switch", creator.as_ref(), &id_bytes, &bump];
+
+        // Persist the in-flight flag so settlement instructions reached through the
+        // callback's CPI see it and refuse to run; a failed CPI reverts it with the rest
+        ctx.accounts.switch.settling = true;
+        ctx.accounts.switch.exit(&crate::ID)?;
+
+        // Invoke before touching other state so a failed CPI leaves the switch untouched
+        invoke_signed(
+            &instruction,
+            &[
+                ctx.accounts.switch.to_account_info(),
+                ctx.accounts.callback_program.to_account_info(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        let switch = &mut ctx.accounts.switch;
+        finish_callback(switch);
+        switch.last_actor = Pubkey::default();
+        bump_seq(switch)?;
+        freeze_hash(switch, switch_key, current_time);
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            Pubkey::default(),
+            LOG_CALLBACK,
+            current_time,
+        )?;
+
+        emit!(CallbackTriggered {
+            switch: switch_key,
+            callback_program,
+            data_length: switch.data_length,
             timestamp: current_time,
+            seq: switch.seq,
         });
 
         Ok(())
@@ -92,15 +543,124 @@ mod dead_mans_switch {
 
     /// Manually marks a switch as expired (can be called by anyone)
     pub fn mark_expired(ctx: Context<MarkExpired>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
         let switch = &mut ctx.accounts.switch;
         let current_time = Clock::get()?.unix_timestamp;
 
-        // Only mark if actually expired and not already marked
-        if is_expired(switch, current_time) && !switch.expired {
+        require_not_settling(switch)?;
+
+        // Only mark if active, actually expired and not already marked
+        if switch.active && is_revealable(switch, current_time) && !switch.expired {
             switch.expired = true;
+            switch.last_actor = Pubkey::default();
+            bump_seq(switch)?;
+            let switch_key = switch.key();
+            freeze_hash(switch, switch_key, current_time);
+            append_log(
+                ctx.accounts.log.as_deref_mut(),
+                switch.has_log,
+                Pubkey::default(),
+                LOG_EXPIRED,
+                current_time,
+            )?;
             emit!(SwitchExpired {
                 switch: switch.key(),
                 timestamp: current_time,
+                seq: switch.seq,
+            });
+            emit!(SwitchExpiredNotification {
+                switch: switch.key(),
+                owner: switch.owner,
+                beneficiary: switch.beneficiary,
+                emergency_contact: switch.emergency_contact,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Emits BeneficiaryNotified once the switch is within its notice window (anyone)
+    ///
+    /// Idempotent: outside the window, or once the notice went out for the current
+    /// countdown, this does nothing. The next ping re-arms the notice.
+    pub fn notify_beneficiary(ctx: Context<NotifyBeneficiary>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let beneficiary = match switch.beneficiary {
+            Some(beneficiary) => beneficiary,
+            None => return Ok(()),
+        };
+        if !switch.active
+            || switch.beneficiary_notice == 0
+            || switch.beneficiary_notified
+            || is_countdown_paused(switch)
+            || is_revealable(switch, current_time)
+        {
+            return Ok(());
+        }
+
+        let expires_at = expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+        if expires_at.saturating_sub(current_time) > switch.beneficiary_notice {
+            return Ok(());
+        }
+
+        switch.beneficiary_notified = true;
+        switch.last_actor = Pubkey::default();
+        bump_seq(switch)?;
+
+        emit!(BeneficiaryNotified {
+            switch: switch.key(),
+            beneficiary,
+            expires_at,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the switch's current liveness phase (read-only)
+    ///
+    /// A lapsed interval makes the switch missing; it is dead once `confirm_interval` has
+    /// also passed without a ping.
+    pub fn get_phase(ctx: Context<GetPhase>) -> Result<u8> {
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if !switch.active {
+            return Ok(PHASE_ALIVE);
+        }
+        Ok(current_phase(switch, current_time))
+    }
+
+    /// Records the current liveness phase, emitting PhaseChanged if it moved (anyone)
+    pub fn update_phase(ctx: Context<UpdatePhase>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+
+        let old_phase = switch.phase;
+        let new_phase = current_phase(switch, current_time);
+        if new_phase != old_phase {
+            switch.phase = new_phase;
+            switch.last_actor = Pubkey::default();
+            bump_seq(switch)?;
+
+            emit!(PhaseChanged {
+                switch: switch.key(),
+                old_phase,
+                new_phase,
+                timestamp: current_time,
+                seq: switch.seq,
             });
         }
 
@@ -114,16 +674,71 @@ mod dead_mans_switch {
         Ok(is_expired(switch, current_time))
     }
 
+    /// Whether the switch is within its stored warning lead time of expiring (read-only)
+    pub fn should_warn(ctx: Context<ShouldWarn>) -> Result<bool> {
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if !switch.active
+            || switch.warn_threshold == 0
+            || is_countdown_paused(switch)
+            || is_expired(switch, current_time)
+        {
+            return Ok(false);
+        }
+
+        let expires_at = expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+        Ok(expires_at.saturating_sub(current_time) <= switch.warn_threshold)
+    }
+
+    /// Check whether the switch would be expired at `at_time` (read-only)
+    pub fn would_be_expired(ctx: Context<WouldBeExpired>, at_time: i64) -> Result<bool> {
+        let switch = &ctx.accounts.switch;
+        require!(at_time >= switch.created_at, ErrorCode::InvalidTimestamp);
+
+        Ok(is_expired(switch, at_time))
+    }
+
+    /// Returns a 0-100 score for how reliably the owner pings (read-only)
+    pub fn get_liveness_score(ctx: Context<GetLivenessScore>) -> Result<u8> {
+        Ok(liveness_score(&ctx.accounts.switch))
+    }
+
+    /// Check expiration against both time and the switch's oracle condition
+    ///
+    /// The switch is actionable only when time-expired and the oracle value (a
+    /// little-endian i64 at the start of the oracle account's data) is at or above
+    /// the configured threshold.
+    pub fn check_expiration_with_oracle(ctx: Context<CheckExpirationWithOracle>) -> Result<bool> {
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let value = read_oracle_value(&ctx.accounts.oracle.to_account_info())?;
+        let condition_met = value >= switch.oracle_threshold;
+        let actionable = is_expired(switch, current_time) && condition_met;
+
+        if actionable {
+            emit!(OracleConditionMet {
+                switch: switch.key(),
+                oracle: ctx.accounts.oracle.key(),
+                value,
+                threshold: switch.oracle_threshold,
+                timestamp: current_time,
+            });
+        }
+
+        Ok(actionable)
+    }
+
     /// Get switch info with expiration status (read-only)
     pub fn get_switch_info(ctx: Context<GetSwitchInfo>) -> Result<SwitchInfo> {
         let switch = &ctx.accounts.switch;
         let current_time = Clock::get()?.unix_timestamp;
         let expired = is_expired(switch, current_time);
 
-        let expiration_time = switch
-            .last_ping
-            .checked_add(switch.ping_interval)
-            .unwrap_or(i64::MAX);
+        let expiration_time =
+            expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+        let expiration_status = compute_expiration_status(switch, current_time)?;
 
         Ok(SwitchInfo {
             owner: switch.owner,
@@ -133,120 +748,9350 @@ mod dead_mans_switch {
             created_at: switch.created_at,
             expiration_time,
             current_time,
+            expiration_status,
+            last_actor: switch.last_actor,
+            warn_threshold: switch.warn_threshold,
+            on_expiry: switch.on_expiry,
+            storage_kind: switch.storage_kind,
+            storage_ref: switch.storage_ref,
+            max_data_size: switch.max_data_size,
+            beneficiary_pubkey: switch.beneficiary_pubkey,
+            external_id: switch.external_id,
+            max_lifetime: switch.max_lifetime,
+            jurisdiction: switch.jurisdiction,
         })
     }
-}
 
-/// Checks if a switch is expired
-fn is_expired(switch: &DeadManSwitch, current_time: i64) -> bool {
-    switch
-        .last_ping
-        .checked_add(switch.ping_interval)
-        .map_or(true, |expiration| current_time >= expiration)
-}
+    /// Returns the raw account bytes after the discriminator, starting at `offset`
+    ///
+    /// Return data is capped at 1024 bytes, so the layout is served in chunks of at most
+    /// `RAW_STATE_CHUNK` bytes; an empty result means `offset` is past the end.
+    pub fn get_raw_state(ctx: Context<GetRawState>, offset: u32) -> Result<Vec<u8>> {
+        let info = ctx.accounts.switch.to_account_info();
+        let data = info.try_borrow_data()?;
+        let raw = &data[8..];
 
-/// Main switch storage account
-#[account]
-pub struct DeadManSwitch {
-    pub owner: Pubkey,                       // Switch owner (32 bytes)
-    pub last_ping: i64,                      // Last ping timestamp (8 bytes)
-    pub ping_interval: i64,                  // Required ping interval (8 bytes)
-    pub encrypted_data: [u8; MAX_DATA_SIZE], // Encrypted message (512 bytes fixed)
-    pub data_length: u16,                    // Actual data length (2 bytes)
-    pub created_at: i64,                     // Creation timestamp (8 bytes)
-    pub bump: u8,                            // PDA bump (1 byte)
-    pub expired: bool,                       // Expiration status (1 byte)
-}
+        let start = (offset as usize).min(raw.len());
+        let end = start.saturating_add(RAW_STATE_CHUNK).min(raw.len());
 
-impl DeadManSwitch {
-    /// Get the actual encrypted data as a slice
-    pub fn get_encrypted_data(&self) -> &[u8] {
-        &self.encrypted_data[..self.data_length as usize]
+        Ok(raw[start..end].to_vec())
     }
-}
 
-/// Switch information struct for client responses
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct SwitchInfo {
-    pub owner: Pubkey,
-    pub expired: bool,
-    pub last_ping: i64,
-    pub ping_interval: i64,
-    pub created_at: i64,
-    pub expiration_time: i64,
-    pub current_time: i64,
-}
+    /// Returns a chunk of the versioned state export, starting at `offset` (read-only)
+    ///
+    /// The export is `EXPORT_MAGIC`, `EXPORT_VERSION`, the body length as a little-endian
+    /// u32, then the raw account bytes after the discriminator. It is served in
+    /// `RAW_STATE_CHUNK`-byte chunks; an empty result means `offset` is past the end.
+    pub fn export_state(ctx: Context<ExportState>, offset: u32) -> Result<Vec<u8>> {
+        let info = ctx.accounts.switch.to_account_info();
+        let data = info.try_borrow_data()?;
+        let body = &data[8..];
 
-// ===== Account Validation Structs ===== //
+        let mut blob = Vec::with_capacity(EXPORT_HEADER_SIZE + body.len());
+        blob.extend_from_slice(&EXPORT_MAGIC);
+        blob.push(EXPORT_VERSION);
+        blob.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        blob.extend_from_slice(body);
 
-#[derive(Accounts)]
-#[instruction(id: u64, ping_interval: i64, encrypted_data: Vec<u8>)]
-pub struct CreateSwitch<'info> {
-    #[account(
-        init,
-        payer = owner,
-        // Increased space by 1 byte for expired flag
-        space = 8 + 32 + 8 + 8 + MAX_DATA_SIZE + 2 + 8 + 1 + 1,
-        seeds = [b"switch", owner.key.as_ref(), &id.to_le_bytes()],
-        bump
-    )]
-    pub switch: Account<'info, DeadManSwitch>,
+        let start = (offset as usize).min(blob.len());
+        let end = start.saturating_add(RAW_STATE_CHUNK).min(blob.len());
 
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        Ok(blob[start..end].to_vec())
+    }
 
-#[derive(Accounts)]
-pub struct Ping<'info> {
-    #[account(
-        mut,
-        has_one = owner,
-    )]
-    pub switch: Account<'info, DeadManSwitch>,
-    pub owner: Signer<'info>,
-}
+    /// Sets or clears the key allowed to claim the data after expiry
+    pub fn set_beneficiary(
+        ctx: Context<SetBeneficiary>,
+        beneficiary: Option<Pubkey>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
 
-#[derive(Accounts)]
-pub struct MarkExpired<'info> {
-    #[account(mut)]
-    pub switch: Account<'info, DeadManSwitch>,
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.beneficiary = beneficiary;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets or clears the key allowed to close the switch alongside the owner
+    pub fn set_close_authority(
+        ctx: Context<SetCloseAuthority>,
+        close_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.close_authority = close_authority;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets or clears the oracle condition required alongside expiry
+    pub fn set_oracle(
+        ctx: Context<SetOracle>,
+        oracle: Option<Pubkey>,
+        oracle_threshold: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.oracle = oracle;
+        switch.oracle_threshold = oracle_threshold;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Links a companion switch that must also expire before the data can be claimed
+    pub fn set_companion(ctx: Context<SetCompanion>, companion: Option<Pubkey>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(companion != Some(switch.key()), ErrorCode::InvalidCompanion);
+
+        switch.companion = companion;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(CompanionLinked {
+            switch: switch.key(),
+            companion,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets whether a successful claim also closes the switch
+    pub fn set_auto_close_on_claim(
+        ctx: Context<SetAutoCloseOnClaim>,
+        auto_close_on_claim: bool,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.auto_close_on_claim = auto_close_on_claim;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the cooling-off period between expiry and settlement
+    pub fn set_reveal_delay(ctx: Context<SetRevealDelay>, reveal_delay: i64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            (0..=MAX_REVEAL_DELAY).contains(&reveal_delay),
+            ErrorCode::InvalidRevealDelay
+        );
+
+        switch.reveal_delay = reveal_delay;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how long a missing switch must stay silent before it counts as dead
+    ///
+    /// The confirmation interval also delays settlement, on top of any reveal delay.
+    pub fn set_confirm_interval(
+        ctx: Context<SetConfirmInterval>,
+        confirm_interval: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            (0..=MAX_CONFIRM_INTERVAL).contains(&confirm_interval),
+            ErrorCode::InvalidConfirmInterval
+        );
+
+        switch.confirm_interval = confirm_interval;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Makes the countdown wait for the first ping (only before the switch is ever pinged)
+    pub fn set_require_activation_ping(
+        ctx: Context<SetRequireActivationPing>,
+        require_activation_ping: bool,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(switch.ping_count == 0, ErrorCode::AlreadyActivated);
+
+        switch.require_activation_ping = require_activation_ping;
+        // 0 marks the countdown as not started; turning the flag off starts it now
+        switch.last_ping = if require_activation_ping {
+            0
+        } else {
+            current_time
+        };
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets or clears the witness who must co-sign claims (owner only, before expiry)
+    pub fn set_witness(ctx: Context<SetWitness>, witness: Option<Pubkey>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.witness = witness;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets or clears the key alerted when the switch expires (notification only)
+    pub fn set_emergency_contact(
+        ctx: Context<SetEmergencyContact>,
+        emergency_contact: Option<Pubkey>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.emergency_contact = emergency_contact;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Mirrors the encrypted data into a backup PDA (owner only, while active)
+    pub fn create_backup(ctx: Context<CreateBackup>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(!switch.burned, ErrorCode::DataBurned);
+
+        let backup = &mut ctx.accounts.backup;
+        backup.switch = switch.key();
+        backup.owner = switch.owner;
+        backup.beneficiary = switch.beneficiary;
+        backup.encrypted_data = switch.encrypted_data;
+        backup.data_length = switch.data_length;
+        backup.data_hash = switch.data_hash;
+        backup.expired_at = 0; // Not yet expired
+        backup.bump = ctx.bumps.backup;
+        backup.burned = false;
+        switch.has_backup = true;
+
+        emit!(BackupCreated {
+            switch: switch.key(),
+            backup: backup.key(),
+            data_hash: backup.data_hash,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Snapshots the reveal time into the backup (can be called by anyone once revealable)
+    pub fn sync_backup(ctx: Context<SyncBackup>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let backup = &mut ctx.accounts.backup;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!switch.burned, ErrorCode::DataBurned);
+        require!(is_revealable(switch, current_time), ErrorCode::NotExpired);
+
+        // Data updated since the backup was taken is mirrored again before the snapshot
+        if backup.data_hash != switch.data_hash {
+            backup.encrypted_data = switch.encrypted_data;
+            backup.data_length = switch.data_length;
+            backup.data_hash = switch.data_hash;
+        }
+
+        // Only the first snapshot counts; a ping clears it again
+        if backup.expired_at == 0 {
+            backup.expired_at = reveal_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+            backup.beneficiary = switch.beneficiary;
+        }
+
+        switch.last_actor = Pubkey::default();
+        bump_seq(switch)?;
+
+        Ok(())
+    }
+
+    /// Returns the backed-up data to the snapshotted beneficiary once the snapshotted
+    /// reveal time has passed
+    ///
+    /// Only the backup is read, so this keeps working after the primary is closed.
+    pub fn claim_from_backup(ctx: Context<ClaimFromBackup>) -> Result<Vec<u8>> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let backup = &ctx.accounts.backup;
+        let claimer = *ctx.accounts.beneficiary.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // The backup stands alone, so it still releases after the primary is closed
+        require!(!backup.burned, ErrorCode::DataBurned);
+        require!(
+            backup.expired_at != 0 && current_time >= backup.expired_at,
+            ErrorCode::NotExpired
+        );
+        require!(backup.beneficiary == Some(claimer), ErrorCode::Unauthorized);
+
+        emit!(BackupClaimed {
+            switch: backup.switch,
+            beneficiary: claimer,
+            timestamp: current_time,
+        });
+
+        Ok(backup.get_encrypted_data().to_vec())
+    }
+
+    /// Tops the switch up to the rent-exempt minimum (can be called by anyone)
+    pub fn top_up_rent(ctx: Context<TopUpRent>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch_info = ctx.accounts.switch.to_account_info();
+        let minimum = Rent::get()?.minimum_balance(switch_info.data_len());
+        let shortfall = minimum.saturating_sub(switch_info.lamports());
+
+        // Nothing to do if the account is already rent-exempt
+        if shortfall == 0 {
+            return Ok(());
+        }
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: switch_info,
+                },
+            ),
+            shortfall,
+        )?;
+
+        ctx.accounts.switch.last_actor = ctx.accounts.payer.key();
+        bump_seq(&mut ctx.accounts.switch)?;
+
+        emit!(RentToppedUp {
+            switch: ctx.accounts.switch.key(),
+            payer: ctx.accounts.payer.key(),
+            amount: shortfall,
+            timestamp: Clock::get()?.unix_timestamp,
+            seq: ctx.accounts.switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Check whether the switch currently meets the rent-exempt minimum (read-only)
+    pub fn rent_status(ctx: Context<RentStatus>) -> Result<bool> {
+        let switch_info = ctx.accounts.switch.to_account_info();
+        let rent = Rent::get()?;
+        Ok(rent.is_exempt(switch_info.lamports(), switch_info.data_len()))
+    }
+
+    /// Returns the rent-exempt lamports a new switch holding `data_size` bytes needs
+    ///
+    /// Every switch reserves the full MAX_DATA_SIZE inline whatever its `max_data_size`,
+    /// which only caps writes, so every valid size costs the same.
+    pub fn estimate_rent(_ctx: Context<EstimateRent>, data_size: u16) -> Result<u64> {
+        require!(data_size as usize <= MAX_DATA_SIZE, ErrorCode::DataTooLarge);
+        switch_rent(DeadManSwitch::SPACE)
+    }
+
+    /// Closes an expired switch and refunds rent to the owner
+    pub fn close_switch(ctx: Context<CloseSwitch>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let closer = *ctx.accounts.closer.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!switch.active, ErrorCode::SwitchActive);
+        require!(is_expired(switch, current_time), ErrorCode::NotExpired);
+        require!(
+            current_time >= close_allowed_at(switch)?,
+            ErrorCode::CloseCooldownActive
+        );
+
+        let registry = &mut ctx.accounts.registry;
+        registry.count = registry
+            .count
+            .checked_sub(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        ctx.accounts
+            .stats
+            .record_closed(switch_rent(DeadManSwitch::SPACE)?)?;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            closer,
+            LOG_CLOSED,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        // The close constraint does not serialize the account, so clearing the fields
+        // alone would leave the ciphertext in place; overwrite the raw bytes as well
+        switch.encrypted_data = [0u8; MAX_DATA_SIZE];
+        switch.data_length = 0;
+        let bytes_wiped = wipe_account_data(&switch.to_account_info())?;
+
+        emit!(DataWiped {
+            switch: switch.key(),
+            bytes_wiped,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        emit!(SwitchClosed {
+            switch: switch.key(),
+            owner: switch.owner,
+            closer,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Closes an active switch created by mistake and refunds rent to the owner
+    ///
+    /// Only allowed within `CANCEL_WINDOW` of creation, before the first ping and
+    /// before expiry.
+    pub fn cancel(ctx: Context<CancelSwitch>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(switch.ping_count == 0, ErrorCode::CancelWindowClosed);
+        require!(
+            current_time < switch.created_at.saturating_add(CANCEL_WINDOW),
+            ErrorCode::CancelWindowClosed
+        );
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        let registry = &mut ctx.accounts.registry;
+        registry.count = registry
+            .count
+            .checked_sub(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        ctx.accounts.stats.record_deactivated()?;
+        ctx.accounts
+            .stats
+            .record_closed(switch_rent(DeadManSwitch::SPACE)?)?;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_CLOSED,
+            current_time,
+        )?;
+
+        switch.active = false;
+        clear_backup_snapshot(ctx.accounts.backup.as_deref_mut());
+        switch.last_actor = owner;
+        bump_seq(switch)?;
+
+        // Same as close_switch: the close constraint leaves the raw bytes in place
+        switch.encrypted_data = [0u8; MAX_DATA_SIZE];
+        switch.data_length = 0;
+        let bytes_wiped = wipe_account_data(&switch.to_account_info())?;
+
+        emit!(DataWiped {
+            switch: switch.key(),
+            bytes_wiped,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        emit!(SwitchCancelled {
+            switch: switch.key(),
+            owner,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Check expiration for every switch in remaining accounts (read-only)
+    ///
+    /// Results are returned in input order. Accounts that are not valid switches
+    /// are reported as None instead of aborting the whole batch.
+    pub fn check_expiration_batch(ctx: Context<CheckExpirationBatch>) -> Result<Vec<Option<bool>>> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let statuses = ctx
+            .remaining_accounts
+            .iter()
+            .map(|info| load_switch(info).map(|switch| is_expired(&switch, current_time)))
+            .collect();
+
+        Ok(statuses)
+    }
+
+    /// Packs one status byte per switch in remaining accounts (read-only)
+    ///
+    /// Each byte carries `COMPACT_EXPIRED` and `COMPACT_ACTIVE`, in input order, so far
+    /// more switches fit in return data than with `check_expiration_batch`. Accounts that
+    /// are not valid switches are reported as 0.
+    pub fn batch_expiration_compact(ctx: Context<CheckExpirationBatch>) -> Result<Vec<u8>> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let statuses = ctx
+            .remaining_accounts
+            .iter()
+            .map(|info| {
+                load_switch(info).map_or(0, |switch| {
+                    let mut status = 0;
+                    if is_expired(&switch, current_time) {
+                        status |= COMPACT_EXPIRED;
+                    }
+                    if switch.active {
+                        status |= COMPACT_ACTIVE;
+                    }
+                    status
+                })
+            })
+            .collect();
+
+        Ok(statuses)
+    }
+
+    /// Returns active switches in remaining accounts expiring within `within_seconds`
+    ///
+    /// Already-expired and malformed accounts are left out, and at most
+    /// `MAX_EXPIRING_RESULTS` keys are returned, in input order.
+    pub fn find_expiring(ctx: Context<FindExpiring>, within_seconds: i64) -> Result<Vec<Pubkey>> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+        require!(within_seconds > 0, ErrorCode::InvalidInterval);
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let expiring = ctx
+            .remaining_accounts
+            .iter()
+            .filter(|info| {
+                load_switch(info).is_some_and(|switch| {
+                    switch.active
+                        && !is_expired(&switch, current_time)
+                        && !is_countdown_paused(&switch)
+                        && tolerant_expiration(&switch, current_time).is_some_and(|at| {
+                            let remaining = at.saturating_sub(current_time);
+                            remaining > 0 && remaining < within_seconds
+                        })
+                })
+            })
+            .map(|info| info.key())
+            .take(MAX_EXPIRING_RESULTS)
+            .collect();
+
+        Ok(expiring)
+    }
+
+    /// Sets whether the data becomes world-readable once the switch expires
+    pub fn set_public_on_expiry(
+        ctx: Context<SetPublicOnExpiry>,
+        public_on_expiry: bool,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.public_on_expiry = public_on_expiry;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the encrypted data to anyone once a public switch has expired
+    pub fn read_public(ctx: Context<ReadPublic>) -> Result<Vec<u8>> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.public_on_expiry, ErrorCode::Unauthorized);
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require_not_settling(switch)?;
+        require!(
+            switch.on_expiry == ON_EXPIRY_PUBLIC,
+            ErrorCode::WrongExpiryAction
+        );
+        require!(is_revealable(switch, current_time), ErrorCode::NotExpired);
+
+        // Only announce the reveal once
+        if !switch.revealed {
+            switch.revealed = true;
+            bump_seq(switch)?;
+            let switch_key = switch.key();
+            freeze_hash(switch, switch_key, current_time);
+            switch.last_actor = *ctx.accounts.reader.key;
+            append_log(
+                ctx.accounts.log.as_deref_mut(),
+                switch.has_log,
+                *ctx.accounts.reader.key,
+                LOG_REVEALED,
+                current_time,
+            )?;
+            emit!(PublicRevealed {
+                switch: switch.key(),
+                reader: ctx.accounts.reader.key(),
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+        }
+
+        Ok(released_data(switch, current_time)?.to_vec())
+    }
+
+    /// Authorizes a short-lived session key to ping on the owner's behalf
+    pub fn grant_session(ctx: Context<GrantSession>, key: Pubkey, duration: i64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            duration > 0 && duration <= MAX_SESSION_DURATION,
+            ErrorCode::InvalidSessionDuration
+        );
+
+        let expires_at = current_time
+            .checked_add(duration)
+            .ok_or(ErrorCode::TimeOverflow)?;
+
+        switch.session_key = Some(key);
+        switch.session_expires_at = expires_at;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SESSION,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SessionGranted {
+            switch: switch.key(),
+            session_key: key,
+            expires_at,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Immediately invalidates the current session key
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let session_key = switch
+            .session_key
+            .take()
+            .ok_or(ErrorCode::NoActiveSession)?;
+        switch.session_expires_at = 0;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SESSION,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SessionRevoked {
+            switch: switch.key(),
+            session_key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Lets a relayer ping on the owner's behalf until an absolute deadline
+    pub fn authorize_proxy(
+        ctx: Context<AuthorizeProxy>,
+        proxy: Pubkey,
+        max_until: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(max_until > current_time, ErrorCode::InvalidProxyDeadline);
+
+        switch.proxy = Some(proxy);
+        switch.proxy_max_until = max_until;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SESSION,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(ProxyAuthorized {
+            switch: switch.key(),
+            proxy,
+            max_until,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Immediately removes the proxy's ping rights
+    pub fn revoke_proxy(ctx: Context<RevokeProxy>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let proxy = switch.proxy.take().ok_or(ErrorCode::NoProxy)?;
+        switch.proxy_max_until = 0;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SESSION,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(ProxyRevoked {
+            switch: switch.key(),
+            proxy,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Permanently disarms the switch so it will never trigger
+    pub fn deactivate_switch(ctx: Context<DeactivateSwitch>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+
+        let switch_key = switch.key();
+        deactivate(switch, &mut ctx.accounts.stats, switch_key, current_time)?;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_DEACTIVATED,
+            current_time,
+        )?;
+
+        Ok(())
+    }
+
+    /// Get the expiration status without masking overflow (read-only)
+    pub fn expiration_status(ctx: Context<GetExpirationStatus>) -> Result<ExpirationStatus> {
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+        compute_expiration_status(switch, current_time)
+    }
+
+    /// Replaces the age-based interval schedule
+    pub fn set_schedule(
+        ctx: Context<SetSchedule>,
+        schedule: [ScheduleTier; SCHEDULE_TIERS],
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(!switch.interval_locked, ErrorCode::IntervalLocked);
+        validate_schedule(&schedule)?;
+
+        switch.schedule = schedule;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Get the ping interval currently in force (read-only)
+    pub fn get_effective_interval(ctx: Context<GetEffectiveInterval>) -> Result<i64> {
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+        Ok(effective_interval(switch, current_time))
+    }
+
+    /// Returns the time by which the next ping must land (read-only)
+    ///
+    /// Composed in this order: `last_ping`, plus the effective interval (schedule tier,
+    /// capped after a light ping), plus `grace_period`, plus `banked_time`, plus any
+    /// maintenance extension, plus the clock tolerance. Inactive switches, ones still waiting for their activation ping and
+    /// unarmed keepalive switches have no deadline and return `i64::MAX`.
+    pub fn next_deadline(ctx: Context<GetNextDeadline>) -> Result<i64> {
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if !switch.active || is_countdown_paused(switch) {
+            return Ok(i64::MAX);
+        }
+
+        Ok(expiration_time(switch, current_time)
+            .and_then(|expiration| expiration.checked_add(switch.clock_tolerance))
+            .ok_or(ErrorCode::TimeOverflow)?)
+    }
+
+    /// Returns the deadline cached at the last mutation (read-only)
+    ///
+    /// The cache is accurate as of the last state change and does not follow time-based
+    /// changes since (schedule tiers, expiry itself); `is_expired` remains authoritative.
+    pub fn get_cached_expiration(ctx: Context<GetCachedExpiration>) -> Result<i64> {
+        Ok(ctx.accounts.switch.cached_expiration)
+    }
+
+    /// Moves the switch to a fresh PDA derived from `new_id`, closing the old one
+    pub fn rotate(ctx: Context<Rotate>, new_id: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let old_switch = &ctx.accounts.old_switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(new_id > 0, ErrorCode::InvalidSwitchId);
+        require!(new_id != old_switch.switch_id, ErrorCode::InvalidSwitchId);
+        require!(old_switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(old_switch, current_time), ErrorCode::Expired);
+
+        reserve_id(&mut ctx.accounts.registry, new_id)?;
+
+        // Copy all state, only seed-derived and bookkeeping fields change
+        let mut state = old_switch.clone().into_inner();
+        state.switch_id = new_id;
+        state.creator = *ctx.accounts.owner.key;
+        state.bump = ctx.bumps.new_switch;
+        // The log and backup PDAs are seeded by the old address, so the new switch has neither
+        state.has_log = false;
+        state.has_backup = false;
+        state.last_actor = *ctx.accounts.owner.key;
+        // The reward's lamports leave with the old switch's rent, so none back it here
+        state.keeper_reward = 0;
+        bump_seq(&mut state)?;
+        ctx.accounts.new_switch.set_inner(state);
+
+        emit!(SwitchRotated {
+            old_switch: ctx.accounts.old_switch.key(),
+            new_switch: ctx.accounts.new_switch.key(),
+            owner: *ctx.accounts.owner.key,
+            new_id,
+            timestamp: current_time,
+            seq: ctx.accounts.new_switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the encrypted data to the beneficiary once the switch has expired
+    ///
+    /// Failed attempts by a named claimer (e.g. before the reveal time) are counted and
+    /// return an empty payload instead of an error so the counter persists. Too many
+    /// failures lock claims for a cooldown period; any other signer is simply rejected.
+    pub fn claim_data(ctx: Context<ClaimData>) -> Result<Vec<u8>> {
+        claim(ctx, None)
+    }
+
+    /// Claims as a member of the committed beneficiary list, proven by a Merkle proof
+    ///
+    /// Leaves are `hash(0x00 || claimer)` and inner nodes `hash(0x01 || a || b)` with the
+    /// children in ascending order. Otherwise behaves exactly like `claim_data`.
+    pub fn claim_data_with_proof(ctx: Context<ClaimData>, proof: Vec<[u8; 32]>) -> Result<Vec<u8>> {
+        require!(
+            ctx.accounts.switch.beneficiary_root.is_some(),
+            ErrorCode::BeneficiaryRootNotSet
+        );
+        require!(proof.len() <= MAX_PROOF_LENGTH, ErrorCode::InvalidProof);
+
+        claim(ctx, Some(&proof))
+    }
+
+    /// Returns the caller's assigned slice of the data after expiry
+    pub fn claim_segment(ctx: Context<ClaimSegment>) -> Result<Vec<u8>> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let claimer = *ctx.accounts.claimer.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require_not_settling(switch)?;
+        require!(!switch.burned, ErrorCode::DataBurned);
+        require!(
+            switch.on_expiry == ON_EXPIRY_BENEFICIARY,
+            ErrorCode::WrongExpiryAction
+        );
+        require!(
+            current_time >= switch.claim_locked_until,
+            ErrorCode::ClaimLocked
+        );
+        // Still cooling off: the owner can ping to cancel, so the attempt isn't counted
+        require!(
+            !is_expired(switch, current_time) || is_revealable(switch, current_time),
+            ErrorCode::NotExpired
+        );
+
+        let segment = switch
+            .segments
+            .iter()
+            .find(|segment| segment.is_set() && segment.beneficiary == claimer)
+            .copied();
+        let segment = match segment {
+            Some(segment) if is_revealable(switch, current_time) => segment,
+            _ => return reject_claim(switch, claimer, false, current_time),
+        };
+
+        let gates = check_claim_gates(
+            switch,
+            &ClaimGateAccounts {
+                witness: ctx.accounts.witness.as_ref(),
+                companion: ctx.accounts.companion.as_deref(),
+                attestation: ctx.accounts.attestation.as_deref(),
+                config: &ctx.accounts.config,
+                claimer: &ctx.accounts.claimer,
+                fee_recipient: ctx.accounts.fee_recipient.as_ref(),
+                system_program: ctx.accounts.system_program.as_ref(),
+            },
+            current_time,
+        )?;
+
+        switch.last_actor = claimer;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            claimer,
+            LOG_CLAIMED,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+        let switch_key = switch.key();
+        freeze_hash(switch, switch_key, current_time);
+        gates.emit(switch_key, claimer, current_time, switch.seq);
+
+        emit!(SegmentClaimed {
+            switch: switch.key(),
+            beneficiary: claimer,
+            start: segment.start,
+            end: segment.end,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        // Only the part of the segment the stages have already released is returned
+        let released = released_data(switch, current_time)?;
+        let end = (segment.end as usize).min(released.len());
+        require!((segment.start as usize) < end, ErrorCode::StageLocked);
+        Ok(released[segment.start as usize..end].to_vec())
+    }
+
+    /// Returns who first claimed the data and when (read-only)
+    pub fn get_claim_record(ctx: Context<GetClaimRecord>) -> Result<ClaimRecord> {
+        let switch = &ctx.accounts.switch;
+        Ok(ClaimRecord {
+            claimed_by: switch.claimed_by,
+            claimed_at: switch.claimed_at,
+        })
+    }
+
+    /// Returns the current data hash and the one it replaced (read-only)
+    pub fn get_data_chain(ctx: Context<GetDataChain>) -> Result<DataChain> {
+        let switch = &ctx.accounts.switch;
+        Ok(DataChain {
+            data_hash: switch.data_hash,
+            prev_data_hash: switch.prev_data_hash,
+        })
+    }
+
+    /// Returns the concatenated data of two expired switches claimable by the caller
+    ///
+    /// Switches must be passed in ascending address order so the combined payload is
+    /// canonical. Switches that need a witness, companion or attestation must be claimed
+    /// one by one.
+    pub fn claim_combined(ctx: Context<ClaimCombined>) -> Result<Vec<u8>> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let claimer = *ctx.accounts.claimer.key;
+        let current_time = Clock::get()?.unix_timestamp;
+        let first_key = ctx.accounts.first.key();
+        let second_key = ctx.accounts.second.key();
+
+        require!(first_key < second_key, ErrorCode::InvalidSwitchOrder);
+
+        let total_length =
+            ctx.accounts.first.data_length as usize + ctx.accounts.second.data_length as usize;
+        require!(total_length <= RAW_STATE_CHUNK, ErrorCode::CombinedTooLarge);
+
+        let config = &ctx.accounts.config;
+        let logs = [
+            ctx.accounts.first_log.as_deref_mut(),
+            ctx.accounts.second_log.as_deref_mut(),
+        ];
+        let fee_recipients = [
+            ctx.accounts.first_fee_recipient.as_ref(),
+            ctx.accounts.second_fee_recipient.as_ref(),
+        ];
+        let switches = [&mut ctx.accounts.first, &mut ctx.accounts.second];
+        let mut data = Vec::with_capacity(total_length);
+
+        for ((switch, log), fee_recipient) in switches.into_iter().zip(logs).zip(fee_recipients) {
+            require!(switch.active, ErrorCode::SwitchInactive);
+            require_not_settling(switch)?;
+            require!(!switch.burned, ErrorCode::DataBurned);
+            require!(
+                switch.on_expiry == ON_EXPIRY_BENEFICIARY,
+                ErrorCode::WrongExpiryAction
+            );
+            require!(
+                current_time >= switch.claim_locked_until,
+                ErrorCode::ClaimLocked
+            );
+            require!(
+                is_claimable_by(switch, &claimer, current_time),
+                ErrorCode::Unauthorized
+            );
+            require!(
+                switch.witness.is_none() && switch.companion.is_none(),
+                ErrorCode::CombinedClaimUnsupported
+            );
+            let gates = check_claim_gates(
+                switch,
+                &ClaimGateAccounts {
+                    witness: None,
+                    companion: None,
+                    attestation: None,
+                    config,
+                    claimer: &ctx.accounts.claimer,
+                    fee_recipient,
+                    system_program: ctx.accounts.system_program.as_ref(),
+                },
+                current_time,
+            )?;
+
+            switch.claim_attempts = 0;
+            if switch.claimed_by.is_none() {
+                switch.claimed_by = Some(claimer);
+                switch.claimed_at = Some(current_time);
+            }
+            switch.last_actor = claimer;
+            append_log(log, switch.has_log, claimer, LOG_CLAIMED, current_time)?;
+
+            bump_seq(switch)?;
+            let switch_key = switch.key();
+            freeze_hash(switch, switch_key, current_time);
+            gates.emit(switch_key, claimer, current_time, switch.seq);
+
+            data.extend_from_slice(released_data(switch, current_time)?);
+        }
+
+        emit!(CombinedClaimed {
+            first: first_key,
+            second: second_key,
+            beneficiary: claimer,
+            first_seq: ctx.accounts.first.seq,
+            second_seq: ctx.accounts.second.seq,
+            timestamp: current_time,
+        });
+
+        Ok(data)
+    }
+
+    /// Deactivates every active switch in remaining accounts owned by the signer
+    ///
+    /// Accounts that are malformed, owned by someone else, already inactive, or that
+    /// carry a custody log are skipped.
+    pub fn deactivate_all<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeactivateAll<'info>>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+        let mut count: u32 = 0;
+
+        for info in ctx.remaining_accounts.iter() {
+            if !info.is_writable {
+                continue;
+            }
+            let mut switch = match Account::<DeadManSwitch>::try_from(info) {
+                Ok(switch) => switch,
+                Err(_) => continue,
+            };
+            // Logged switches need their custody log, so they go through deactivate_switch
+            if switch.owner != owner || !switch.active || switch.has_log {
+                continue;
+            }
+
+            let switch_key = switch.key();
+            deactivate(
+                &mut switch,
+                &mut ctx.accounts.stats,
+                switch_key,
+                current_time,
+            )?;
+            switch.exit(&crate::ID)?;
+            count += 1;
+        }
+
+        emit!(BatchDeactivated {
+            owner,
+            count,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Closes every inactive, expired switch in remaining accounts, refunding the owner
+    ///
+    /// The signer must be the owner or each switch's close authority. Accounts that are
+    /// malformed, belong to another owner, are not closable by the signer, are still
+    /// active, unexpired or cooling down, or carry a custody log are skipped.
+    pub fn close_all_eligible<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseAllEligible<'info>>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let owner = ctx.accounts.owner.key();
+        let closer = *ctx.accounts.closer.key;
+        let current_time = Clock::get()?.unix_timestamp;
+        let mut count: u32 = 0;
+        let mut lamports: u64 = 0;
+
+        for info in ctx.remaining_accounts.iter() {
+            if !info.is_writable {
+                continue;
+            }
+            let mut switch = match Account::<DeadManSwitch>::try_from(info) {
+                Ok(switch) => switch,
+                Err(_) => continue,
+            };
+            let closable_by_signer = closer == owner || switch.close_authority == Some(closer);
+            // Logged switches need their custody log, so they go through close_switch
+            if switch.owner != owner
+                || !closable_by_signer
+                || switch.active
+                || !is_expired(&switch, current_time)
+                || current_time < close_allowed_at(&switch)?
+                || switch.has_log
+            {
+                continue;
+            }
+
+            let registry = &mut ctx.accounts.registry;
+            registry.count = registry
+                .count
+                .checked_sub(1)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            ctx.accounts
+                .stats
+                .record_closed(switch_rent(DeadManSwitch::SPACE)?)?;
+            bump_seq(&mut switch)?;
+
+            let switch_key = switch.key();
+            let recovered = info.lamports();
+            let bytes_wiped = wipe_account_data(info)?;
+
+            emit!(DataWiped {
+                switch: switch_key,
+                bytes_wiped,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+
+            emit!(SwitchClosed {
+                switch: switch_key,
+                owner,
+                closer,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+
+            switch.close(ctx.accounts.owner.to_account_info())?;
+            count += 1;
+            lamports = lamports
+                .checked_add(recovered)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+        }
+
+        emit!(BatchClosed {
+            owner,
+            closer,
+            count,
+            lamports,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Opens the on-chain custody log; once open, every logged action must supply it
+    pub fn init_log(ctx: Context<InitLog>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        let log = &mut ctx.accounts.log;
+        log.switch = switch.key();
+        log.len = 0;
+        log.entries = [LogEntry::default(); MAX_LOG_ENTRIES];
+        log.bump = ctx.bumps.log;
+        log.append(*ctx.accounts.owner.key, LOG_OPENED, current_time)?;
+
+        switch.has_log = true;
+        switch.last_actor = *ctx.accounts.owner.key;
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Get all custody log entries in order (read-only)
+    pub fn read_log(ctx: Context<ReadLog>) -> Result<Vec<LogEntry>> {
+        Ok(ctx.accounts.log.entries().to_vec())
+    }
+
+    /// Creates the program config; the first caller becomes admin
+    pub fn init_config(ctx: Context<InitConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = *ctx.accounts.admin.key;
+        config.bump = ctx.bumps.config;
+        config.max_delegates = DEFAULT_MAX_DELEGATES;
+        config.clock_tolerance = 0;
+        config.treasury = *ctx.accounts.admin.key;
+        config.creation_fee = 0;
+        config.required_confirmations = [0; SENSITIVITY_LEVELS];
+        config.global_paused = false;
+
+        Ok(())
+    }
+
+    /// Creates the shared keeper reward pool (admin only)
+    pub fn init_reward_pool(ctx: Context<InitRewardPool>, reward_per_reveal: u64) -> Result<()> {
+        require!(
+            reward_per_reveal <= MAX_KEEPER_REWARD,
+            ErrorCode::RewardTooLarge
+        );
+
+        let pool = &mut ctx.accounts.pool;
+        pool.balance = 0;
+        pool.reward_per_reveal = reward_per_reveal;
+        pool.bump = ctx.bumps.pool;
+
+        Ok(())
+    }
+
+    /// Deposits lamports into the reward pool (admin only)
+    pub fn fund_pool(ctx: Context<FundPool>, amount: u64) -> Result<()> {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.pool.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.balance = pool
+            .balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        emit!(PoolFunded {
+            amount,
+            balance: pool.balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws lamports from the reward pool (admin only)
+    pub fn withdraw_pool(ctx: Context<WithdrawPool>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(amount <= pool.balance, ErrorCode::PoolExhausted);
+
+        pool.balance -= amount;
+        transfer_lamports(
+            &pool.to_account_info(),
+            &ctx.accounts.admin.to_account_info(),
+            amount,
+        )?;
+
+        emit!(PoolWithdrawn {
+            amount,
+            balance: pool.balance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Marks an expired switch as revealed and pays the keeper from the pool
+    pub fn reveal(ctx: Context<Reveal>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let pool = &mut ctx.accounts.pool;
+        let keeper = *ctx.accounts.keeper.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require_not_settling(switch)?;
+        require!(is_revealable(switch, current_time), ErrorCode::NotExpired);
+        require!(!switch.revealed, ErrorCode::AlreadyRevealed);
+
+        let pool_reward = pool.reward_per_reveal;
+        require!(pool_reward <= pool.balance, ErrorCode::PoolExhausted);
+        let escrowed = switch.keeper_reward;
+        let reveal_at = reveal_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+        let escrow_paid = decayed_reward(
+            escrowed,
+            switch.reward_decay_window,
+            current_time.saturating_sub(reveal_at),
+        );
+        let reward = pool_reward
+            .checked_add(escrow_paid)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        switch.expired = true;
+        switch.revealed = true;
+        switch.last_actor = keeper;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            keeper,
+            LOG_KEEPER_REVEAL,
+            current_time,
+        )?;
+
+        pool.balance -= pool_reward;
+        transfer_lamports(
+            &pool.to_account_info(),
+            &ctx.accounts.keeper.to_account_info(),
+            pool_reward,
+        )?;
+        switch.keeper_reward = 0;
+        transfer_lamports(
+            &switch.to_account_info(),
+            &ctx.accounts.keeper.to_account_info(),
+            escrow_paid,
+        )?;
+        // Whatever decayed away goes back to the owner
+        transfer_lamports(
+            &switch.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            escrowed - escrow_paid,
+        )?;
+
+        bump_seq(switch)?;
+        let switch_key = switch.key();
+        freeze_hash(switch, switch_key, current_time);
+
+        emit!(SwitchRevealed {
+            switch: switch.key(),
+            keeper,
+            reward,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Escrows lamports on the switch as an extra reward for the revealing keeper
+    pub fn escrow_reward(ctx: Context<EscrowReward>, amount: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let switch = &ctx.accounts.switch;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        let keeper_reward = switch
+            .keeper_reward
+            .checked_add(amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(
+            amount > 0 && keeper_reward <= MAX_KEEPER_REWARD,
+            ErrorCode::RewardTooLarge
+        );
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.switch.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let switch = &mut ctx.accounts.switch;
+        switch.keeper_reward = keeper_reward;
+        switch.last_actor = *ctx.accounts.owner.key;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(RewardEscrowed {
+            switch: switch.key(),
+            amount,
+            keeper_reward,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how long the escrowed keeper reward takes to decay after expiry
+    pub fn set_reward_decay_window(
+        ctx: Context<SetRewardDecayWindow>,
+        reward_decay_window: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            (0..=MAX_REWARD_DECAY_WINDOW).contains(&reward_decay_window),
+            ErrorCode::InvalidDecayWindow
+        );
+
+        switch.reward_decay_window = reward_decay_window;
+        switch.last_actor = *ctx.accounts.owner.key;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the escrowed keeper reward of an inactive, unrevealed switch to the owner
+    pub fn reclaim_reward(ctx: Context<ReclaimReward>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!switch.active, ErrorCode::SwitchActive);
+        require!(!switch.revealed, ErrorCode::AlreadyRevealed);
+        require!(switch.keeper_reward > 0, ErrorCode::NoEscrowedReward);
+
+        let amount = switch.keeper_reward;
+        let switch_info = switch.to_account_info();
+        let minimum = Rent::get()?.minimum_balance(switch_info.data_len());
+        require!(
+            switch_info.lamports().saturating_sub(amount) >= minimum,
+            ErrorCode::RentExemptionViolated
+        );
+
+        switch.keeper_reward = 0;
+        transfer_lamports(&switch_info, &ctx.accounts.owner.to_account_info(), amount)?;
+
+        switch.last_actor = owner;
+        bump_seq(switch)?;
+
+        emit!(RewardReclaimed {
+            switch: switch.key(),
+            owner,
+            amount,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Creates a reusable set of switch settings
+    pub fn create_template(
+        ctx: Context<CreateTemplate>,
+        template_id: u64,
+        ping_interval: i64,
+        grace_period: i64,
+        encryption_scheme: u8,
+        content_type: u8,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        require!(
+            ping_interval >= MIN_PING_INTERVAL && ping_interval <= MAX_PING_INTERVAL,
+            ErrorCode::InvalidInterval
+        );
+        require!(
+            (0..=MAX_GRACE_PERIOD).contains(&grace_period),
+            ErrorCode::InvalidGracePeriod
+        );
+
+        let template = &mut ctx.accounts.template;
+        template.owner = *ctx.accounts.owner.key;
+        template.template_id = template_id;
+        template.ping_interval = ping_interval;
+        template.grace_period = grace_period;
+        template.encryption_scheme = encryption_scheme;
+        template.content_type = content_type;
+        template.bump = ctx.bumps.template;
+
+        emit!(TemplateCreated {
+            template: template.key(),
+            owner: template.owner,
+            template_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Creates a new switch taking all non-data settings from a template
+    pub fn create_switch_from_template(
+        ctx: Context<CreateSwitchFromTemplate>,
+        id: u64,
+        encrypted_data: Vec<u8>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let owner = *ctx.accounts.owner.key;
+        let template = &ctx.accounts.template;
+
+        let fee = charge_creation_fee(
+            &ctx.accounts.config,
+            ctx.accounts.treasury.as_ref(),
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+        )?;
+        register_switch(&mut ctx.accounts.registry, owner, ctx.bumps.registry)?;
+        reserve_id(&mut ctx.accounts.registry, id)?;
+        ctx.accounts
+            .stats
+            .record_created(switch_rent(DeadManSwitch::SPACE)?)?;
+
+        let switch = &mut ctx.accounts.switch;
+        init_switch(
+            switch,
+            owner,
+            id,
+            template.ping_interval,
+            &encrypted_data,
+            ctx.bumps.switch,
+            current_time,
+        )?;
+        switch.grace_period = template.grace_period;
+        switch.encryption_scheme = template.encryption_scheme;
+        switch.content_type = template.content_type;
+
+        refresh_cached_expiration(switch, current_time);
+        let expiration_time =
+            expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+
+        emit!(SwitchCreated {
+            switch: switch.key(),
+            owner,
+            switch_id: id,
+            ping_interval: template.ping_interval,
+            expiration_time,
+            timestamp: current_time,
+            template: Some(template.key()),
+            seq: switch.seq,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the owner's default settings used when create_switch receives zero values
+    pub fn set_owner_defaults(
+        ctx: Context<SetOwnerDefaults>,
+        ping_interval: i64,
+        grace_period: i64,
+        warn_threshold: i64,
+        encryption_scheme: u8,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        require!(
+            ping_interval >= MIN_PING_INTERVAL && ping_interval <= MAX_PING_INTERVAL,
+            ErrorCode::InvalidInterval
+        );
+        require!(
+            (0..=MAX_GRACE_PERIOD).contains(&grace_period),
+            ErrorCode::InvalidGracePeriod
+        );
+        require!(
+            warn_threshold >= 0 && warn_threshold < ping_interval,
+            ErrorCode::InvalidWarnThreshold
+        );
+
+        let defaults = &mut ctx.accounts.defaults;
+        defaults.owner = *ctx.accounts.owner.key;
+        defaults.ping_interval = ping_interval;
+        defaults.grace_period = grace_period;
+        defaults.warn_threshold = warn_threshold;
+        defaults.encryption_scheme = encryption_scheme;
+        defaults.bump = ctx.bumps.defaults;
+
+        emit!(OwnerDefaultsUpdated {
+            owner: defaults.owner,
+            ping_interval,
+            grace_period,
+            warn_threshold,
+            encryption_scheme,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Check whether `candidate` could claim the data right now (read-only)
+    pub fn can_claim(ctx: Context<CanClaim>, candidate: Pubkey) -> Result<bool> {
+        let switch = &ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        Ok(switch.active
+            && current_time >= switch.claim_locked_until
+            && is_claimable_by(switch, &candidate, current_time))
+    }
+
+    /// Changes the ping interval
+    ///
+    /// Shortening the interval can expire the switch immediately. Pass
+    /// `reset_on_shorten = true` to restart the timer from now instead.
+    pub fn update_interval(
+        ctx: Context<UpdateInterval>,
+        new_interval: i64,
+        reset_on_shorten: bool,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(!switch.interval_locked, ErrorCode::IntervalLocked);
+        require!(
+            new_interval >= MIN_PING_INTERVAL && new_interval <= MAX_PING_INTERVAL,
+            ErrorCode::InvalidInterval
+        );
+        require!(
+            new_interval > switch.warn_threshold,
+            ErrorCode::InvalidWarnThreshold
+        );
+        require!(
+            switch
+                .max_lifetime
+                .map_or(true, |max_lifetime| max_lifetime > new_interval),
+            ErrorCode::InvalidMaxLifetime
+        );
+
+        let old_interval = switch.ping_interval;
+        let timer_reset = reset_on_shorten && new_interval < old_interval;
+
+        switch.ping_interval = new_interval;
+        if timer_reset {
+            switch.last_ping = current_time;
+        }
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(IntervalUpdated {
+            switch: switch.key(),
+            old_interval,
+            new_interval,
+            timer_reset,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the program-wide statistics account (can be called by anyone, once)
+    pub fn init_global_stats(ctx: Context<InitGlobalStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.stats;
+        stats.total_created = 0;
+        stats.active_count = 0;
+        stats.total_rent_locked = 0;
+        stats.bump = ctx.bumps.stats;
+
+        Ok(())
+    }
+
+    /// Get program-wide switch statistics (read-only)
+    pub fn get_global_stats(ctx: Context<GetGlobalStats>) -> Result<GlobalStats> {
+        Ok(ctx.accounts.stats.clone().into_inner())
+    }
+
+    /// Banks extra whole intervals ahead of time, capped at one year in total
+    pub fn prepay(ctx: Context<Prepay>, extra_intervals: u8) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(extra_intervals > 0, ErrorCode::InvalidPrepayment);
+
+        let added = switch
+            .ping_interval
+            .checked_mul(extra_intervals as i64)
+            .ok_or(ErrorCode::TimeOverflow)?;
+        let banked_time = switch
+            .banked_time
+            .checked_add(added)
+            .ok_or(ErrorCode::TimeOverflow)?;
+        require!(banked_time <= MAX_BANKED_TIME, ErrorCode::InvalidPrepayment);
+
+        switch.banked_time = banked_time;
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_PREPAID,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(TimePrepaid {
+            switch: switch.key(),
+            added,
+            banked_time,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Proposes a new owner, who must accept before ownership changes
+    pub fn transfer_ownership(ctx: Context<TransferOwnership>, new_owner: Pubkey) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(new_owner != owner, ErrorCode::InvalidNewOwner);
+
+        switch.pending_owner = Some(new_owner);
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_OWNERSHIP,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(OwnershipTransferProposed {
+            switch: switch.key(),
+            owner,
+            pending_owner: new_owner,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Completes a pending ownership transfer (signed by the pending owner)
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let new_owner = *ctx.accounts.new_owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            switch.pending_owner == Some(new_owner),
+            ErrorCode::Unauthorized
+        );
+
+        // Move the switch between the owners' registries
+        let old_registry = &mut ctx.accounts.old_registry;
+        old_registry.count = old_registry
+            .count
+            .checked_sub(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        register_switch(
+            &mut ctx.accounts.new_registry,
+            new_owner,
+            ctx.bumps.new_registry,
+        )?;
+
+        let previous_owner = switch.owner;
+        switch.owner = new_owner;
+        switch.pending_owner = None;
+        // Session keys were granted by the previous owner
+        switch.session_key = None;
+        switch.session_expires_at = 0;
+        switch.proxy = None;
+        switch.proxy_max_until = 0;
+        switch.last_actor = new_owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            new_owner,
+            LOG_OWNERSHIP,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(OwnershipTransferred {
+            switch: switch.key(),
+            previous_owner,
+            new_owner,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Aborts a pending ownership transfer
+    pub fn cancel_ownership_transfer(ctx: Context<CancelOwnershipTransfer>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let pending_owner = switch
+            .pending_owner
+            .take()
+            .ok_or(ErrorCode::NoPendingTransfer)?;
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_OWNERSHIP,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(OwnershipTransferCancelled {
+            switch: switch.key(),
+            owner,
+            pending_owner,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the per-switch delegate limit (admin only)
+    pub fn set_max_delegates(ctx: Context<SetMaxDelegates>, max_delegates: u8) -> Result<()> {
+        require!(
+            max_delegates as usize <= MAX_DELEGATES,
+            ErrorCode::InvalidMaxDelegates
+        );
+
+        ctx.accounts.config.max_delegates = max_delegates;
+
+        Ok(())
+    }
+
+    /// Sets the clock skew tolerance applied to expiry checks (admin only)
+    ///
+    /// Switches passed as remaining accounts pick it up immediately; others on their next
+    /// sync_clock_tolerance.
+    pub fn set_clock_tolerance<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetClockTolerance<'info>>,
+        clock_tolerance: i64,
+    ) -> Result<()> {
+        require!(
+            (0..=MAX_CLOCK_TOLERANCE).contains(&clock_tolerance),
+            ErrorCode::InvalidClockTolerance
+        );
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        ctx.accounts.config.clock_tolerance = clock_tolerance;
+
+        // Push the new tolerance to the writable switches passed along; expired ones keep
+        // theirs so a larger tolerance can't revive them
+        let admin = *ctx.accounts.admin.key;
+        let current_time = Clock::get()?.unix_timestamp;
+        for info in ctx.remaining_accounts.iter() {
+            if !info.is_writable {
+                continue;
+            }
+            let mut switch = match Account::<DeadManSwitch>::try_from(info) {
+                Ok(switch) => switch,
+                Err(_) => continue,
+            };
+            if !switch.active || is_expired(&switch, current_time) {
+                continue;
+            }
+
+            switch.clock_tolerance = clock_tolerance;
+            switch.last_actor = admin;
+            bump_seq(&mut switch)?;
+            emit!(SwitchUpdated {
+                switch: switch.key(),
+                actor: admin,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+            switch.exit(&crate::ID)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the creation fee and the treasury receiving it (admin only)
+    pub fn set_creation_fee(
+        ctx: Context<SetCreationFee>,
+        treasury: Pubkey,
+        creation_fee: u64,
+    ) -> Result<()> {
+        require!(
+            creation_fee <= MAX_CREATION_FEE,
+            ErrorCode::InvalidCreationFee
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.treasury = treasury;
+        config.creation_fee = creation_fee;
+
+        Ok(())
+    }
+
+    /// Copies the configured clock tolerance onto a switch (anyone, so it can't be skipped)
+    pub fn sync_clock_tolerance(ctx: Context<SyncClockTolerance>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.clock_tolerance = ctx.accounts.config.clock_tolerance;
+        switch.last_actor = *ctx.accounts.authority.key;
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.authority.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Adds a key that may ping the switch on the owner's behalf
+    pub fn add_delegate(ctx: Context<ManageDelegates>, delegate: Pubkey) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let max_delegates = ctx.accounts.config.max_delegates as usize;
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(delegate != owner, ErrorCode::InvalidDelegate);
+        require!(
+            !switch.delegates().contains(&delegate),
+            ErrorCode::DelegateExists
+        );
+
+        let count = switch.delegate_count as usize;
+        require!(
+            count < max_delegates.min(MAX_DELEGATES),
+            ErrorCode::TooManyDelegates
+        );
+
+        switch.delegates[count] = delegate;
+        switch.delegate_count += 1;
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_DELEGATES,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(DelegateAdded {
+            switch: switch.key(),
+            delegate,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Removes a delegate from the switch
+    pub fn remove_delegate(ctx: Context<ManageDelegates>, delegate: Pubkey) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let index = switch
+            .delegates()
+            .iter()
+            .position(|key| *key == delegate)
+            .ok_or(ErrorCode::DelegateNotFound)?;
+
+        // Keep slots contiguous by moving the last delegate into the freed slot
+        let last = switch.delegate_count as usize - 1;
+        switch.delegates[index] = switch.delegates[last];
+        switch.delegates[last] = Pubkey::default();
+        switch.delegate_count -= 1;
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_DELEGATES,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(DelegateRemoved {
+            switch: switch.key(),
+            delegate,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the canonical switch PDA and bump for a creator and ID (read-only)
+    ///
+    /// `owner` is the creating owner; switches keep their address after ownership transfers.
+    pub fn derive_switch_address(
+        _ctx: Context<DeriveSwitchAddress>,
+        owner: Pubkey,
+        id: u64,
+    ) -> Result<(Pubkey, u8)> {
+        Ok(Pubkey::find_program_address(
+            &[b"switch", owner.as_ref(), &id.to_le_bytes()],
+            &crate::ID,
+        ))
+    }
+
+    /// Replaces the encrypted data (owner or PERM_UPDATE_DATA role, before expiry)
+    ///
+    /// When `data_hash` is given it must match the new data; switches with
+    /// `hash_required` set reject updates without one.
+    pub fn update_data(
+        ctx: Context<UpdateData>,
+        encrypted_data: Vec<u8>,
+        data_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let authority = *ctx.accounts.authority.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let computed_hash =
+            replace_data(switch, authority, &encrypted_data, data_hash, current_time)?;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            authority,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(DataUpdated {
+            switch: switch.key(),
+            data_length: switch.data_length,
+            data_hash: computed_hash,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Resets the timer and replaces the encrypted data in one instruction
+    ///
+    /// The signer must pass both the ping and the update_data checks; if either fails
+    /// nothing changes.
+    pub fn ping_and_update(
+        ctx: Context<PingAndUpdate>,
+        encrypted_data: Vec<u8>,
+        data_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let authority = *ctx.accounts.authority.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Switches that have opted into strict mode must keep using counters
+        require!(switch.last_counter == 0, ErrorCode::InvalidCounter);
+
+        let computed_hash =
+            replace_data(switch, authority, &encrypted_data, data_hash, current_time)?;
+        let activating = is_awaiting_activation(switch);
+        let new_expiration = record_ping(switch, authority, current_time)?;
+        clear_backup_snapshot(ctx.accounts.backup.as_deref_mut());
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            authority,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            authority,
+            LOG_PING,
+            current_time,
+        )?;
+
+        emit_ping_events(
+            switch,
+            authority,
+            new_expiration,
+            activating,
+            None,
+            None,
+            current_time,
+        );
+        emit!(DataUpdated {
+            switch: switch.key(),
+            data_length: switch.data_length,
+            data_hash: computed_hash,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets whether update_data must be given the new data hash
+    pub fn set_hash_required(ctx: Context<SetHashRequired>, hash_required: bool) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.hash_required = hash_required;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Records an immutable snapshot of the switch's current state (owner only)
+    pub fn snapshot(ctx: Context<TakeSnapshot>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let snapshot = &mut ctx.accounts.snapshot;
+        snapshot.switch = switch.key();
+        snapshot.index = switch.snapshot_count;
+        snapshot.owner = switch.owner;
+        snapshot.last_ping = switch.last_ping;
+        snapshot.ping_interval = switch.ping_interval;
+        snapshot.data_hash = switch.data_hash;
+        snapshot.timestamp = current_time;
+        snapshot.bump = ctx.bumps.snapshot;
+
+        switch.snapshot_count = switch
+            .snapshot_count
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        switch.last_actor = owner;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SnapshotTaken {
+            switch: switch.key(),
+            snapshot: snapshot.key(),
+            index: snapshot.index,
+            data_hash: snapshot.data_hash,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns a previously recorded snapshot (read-only)
+    pub fn get_snapshot(ctx: Context<GetSnapshot>, _index: u32) -> Result<SwitchSnapshot> {
+        Ok((*ctx.accounts.snapshot).clone())
+    }
+
+    /// Sets how long after deactivation the switch must wait before closing (while active)
+    pub fn set_close_cooldown(ctx: Context<SetCloseCooldown>, close_cooldown: i64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Once deactivated the cooldown is fixed so it can't be cut short
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            (0..=MAX_CLOSE_COOLDOWN).contains(&close_cooldown),
+            ErrorCode::InvalidCloseCooldown
+        );
+
+        switch.close_cooldown = close_cooldown;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the role bits `candidate` holds on the switch (read-only)
+    pub fn get_my_role(ctx: Context<GetMyRole>, candidate: Pubkey) -> Result<u8> {
+        let switch = &ctx.accounts.switch;
+        let mut roles = 0;
+
+        if switch.owner == candidate {
+            roles |= ROLE_OWNER;
+        }
+        if switch.delegates().contains(&candidate) {
+            roles |= ROLE_DELEGATE;
+        }
+        if switch.beneficiary == Some(candidate)
+            || switch
+                .segments
+                .iter()
+                .any(|segment| segment.is_set() && segment.beneficiary == candidate)
+        {
+            roles |= ROLE_BENEFICIARY;
+        }
+        if switch.witness == Some(candidate) {
+            roles |= ROLE_WITNESS;
+        }
+
+        Ok(roles)
+    }
+
+    /// Sets the switch's event verbosity level
+    pub fn set_log_level(ctx: Context<SetLogLevel>, log_level: u8) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(log_level <= LOG_LEVEL_VERBOSE, ErrorCode::InvalidLogLevel);
+
+        switch.log_level = log_level;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Confirms liveness but only extends the deadline by LIGHT_PING_EXTENSION
+    pub fn light_ping(ctx: Context<Ping>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let authority = ctx.accounts.authority.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        // Switches that have opted into strict mode must keep using counters
+        require!(switch.last_counter == 0, ErrorCode::InvalidCounter);
+
+        let activating = is_awaiting_activation(switch);
+        let late_by = late_ping_delay(switch, current_time);
+        record_ping(switch, authority, current_time)?;
+        clear_backup_snapshot(ctx.accounts.backup.as_deref_mut());
+        switch.light_pinged = true;
+        refresh_cached_expiration(switch, current_time);
+        let new_expiration =
+            expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            authority,
+            LOG_PING,
+            current_time,
+        )?;
+
+        if switch.log_level > LOG_LEVEL_MINIMAL {
+            emit!(LightPinged {
+                switch: switch.key(),
+                authority,
+                next_required_ping: new_expiration,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+        }
+
+        if activating {
+            emit!(SwitchActivated {
+                switch: switch.key(),
+                authority,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+        }
+        if let Some(late_by) = late_by {
+            emit!(LatePingAccepted {
+                switch: switch.key(),
+                authority,
+                late_by,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Sets or clears the beneficiary's encryption key for the payload (informational)
+    pub fn set_beneficiary_pubkey(
+        ctx: Context<SetBeneficiaryPubkey>,
+        beneficiary_pubkey: Option<[u8; 32]>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        validate_beneficiary_pubkey(&beneficiary_pubkey)?;
+
+        switch.beneficiary_pubkey = beneficiary_pubkey;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the progressive disclosure stages (owner only, before expiry)
+    pub fn set_stages(
+        ctx: Context<SetStages>,
+        stages: [DisclosureStage; DISCLOSURE_STAGES],
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        validate_stages(&stages, switch.data_length)?;
+
+        switch.stages = stages;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Returns the prefix of the data the beneficiary's stages have unlocked so far
+    pub fn claim_available(ctx: Context<ClaimSegment>) -> Result<Vec<u8>> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let claimer = *ctx.accounts.claimer.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require_not_settling(switch)?;
+        require!(!switch.burned, ErrorCode::DataBurned);
+        require!(
+            switch.on_expiry == ON_EXPIRY_BENEFICIARY,
+            ErrorCode::WrongExpiryAction
+        );
+        require!(
+            current_time >= switch.claim_locked_until,
+            ErrorCode::ClaimLocked
+        );
+        // Still cooling off: the owner can ping to cancel, so the attempt isn't counted
+        require!(
+            !is_expired(switch, current_time) || is_revealable(switch, current_time),
+            ErrorCode::NotExpired
+        );
+        if !is_revealable(switch, current_time) || switch.beneficiary != Some(claimer) {
+            return reject_claim(switch, claimer, false, current_time);
+        }
+
+        let gates = check_claim_gates(
+            switch,
+            &ClaimGateAccounts {
+                witness: ctx.accounts.witness.as_ref(),
+                companion: ctx.accounts.companion.as_deref(),
+                attestation: ctx.accounts.attestation.as_deref(),
+                config: &ctx.accounts.config,
+                claimer: &ctx.accounts.claimer,
+                fee_recipient: ctx.accounts.fee_recipient.as_ref(),
+                system_program: ctx.accounts.system_program.as_ref(),
+            },
+            current_time,
+        )?;
+
+        let length = released_data(switch, current_time)?.len() as u16;
+
+        switch.last_actor = claimer;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            claimer,
+            LOG_CLAIMED,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+        let switch_key = switch.key();
+        freeze_hash(switch, switch_key, current_time);
+        gates.emit(switch_key, claimer, current_time, switch.seq);
+
+        emit!(SegmentClaimed {
+            switch: switch.key(),
+            beneficiary: claimer,
+            start: 0,
+            end: length,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(released_data(switch, current_time)?.to_vec())
+    }
+
+    /// Sets the owner's bookkeeping ID; the seed ID and address are unchanged
+    pub fn set_external_id(ctx: Context<SetExternalId>, external_id: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let old_external_id = switch.external_id;
+        switch.external_id = external_id;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(ExternalIdChanged {
+            switch: switch.key(),
+            old_external_id,
+            external_id,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Records a beneficiary's confirmation of a pending claim
+    ///
+    /// Confirmations count toward the threshold set by the switch's sensitivity and are
+    /// cleared by the next ping.
+    pub fn confirm_claim(ctx: Context<ConfirmClaim>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let confirmer = *ctx.accounts.confirmer.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(is_revealable(switch, current_time), ErrorCode::NotExpired);
+
+        let bit = confirmation_bit(switch, &confirmer).ok_or(ErrorCode::Unauthorized)?;
+        require!(
+            switch.claim_confirmations & bit == 0,
+            ErrorCode::AlreadyConfirmed
+        );
+
+        switch.claim_confirmations |= bit;
+        switch.last_actor = confirmer;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            confirmer,
+            LOG_CONFIRMED,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(ClaimConfirmed {
+            switch: switch.key(),
+            confirmer,
+            confirmations: switch.claim_confirmations.count_ones() as u8,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the switch's sensitivity level (owner only, before expiry)
+    pub fn set_sensitivity(ctx: Context<SetSensitivity>, sensitivity: u8) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            (sensitivity as usize) < SENSITIVITY_LEVELS,
+            ErrorCode::InvalidSensitivity
+        );
+
+        switch.sensitivity = sensitivity;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the claim confirmations required at each sensitivity level (admin only)
+    ///
+    /// Levels must not require fewer confirmations than the levels below them.
+    pub fn set_required_confirmations(
+        ctx: Context<SetRequiredConfirmations>,
+        required_confirmations: [u8; SENSITIVITY_LEVELS],
+    ) -> Result<()> {
+        let max_confirmations = (1 + MAX_SEGMENTS) as u8;
+        require!(
+            required_confirmations
+                .windows(2)
+                .all(|pair| pair[0] <= pair[1])
+                && required_confirmations[SENSITIVITY_LEVELS - 1] <= max_confirmations,
+            ErrorCode::InvalidConfirmationPolicy
+        );
+
+        ctx.accounts.config.required_confirmations = required_confirmations;
+
+        Ok(())
+    }
+
+    /// Destroys the encrypted data but keeps the switch account (owner only, while active)
+    ///
+    /// Unlike close_switch this reclaims no rent; `data_hash` is kept as a record of
+    /// what was burned.
+    pub fn burn_data(ctx: Context<BurnData>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(!switch.burned, ErrorCode::DataBurned);
+
+        let bytes_burned = switch.data_length;
+        switch.encrypted_data = [0u8; MAX_DATA_SIZE];
+        switch.data_length = 0;
+        switch.burned = true;
+
+        // The mirrored copy goes too, otherwise the backup would still release it
+        match ctx.accounts.backup.as_deref_mut() {
+            Some(backup) => {
+                backup.encrypted_data = [0u8; MAX_DATA_SIZE];
+                backup.data_length = 0;
+                backup.burned = true;
+            }
+            None => require!(!switch.has_backup, ErrorCode::BackupRequired),
+        }
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(DataBurned {
+            switch: switch.key(),
+            bytes_burned,
+            data_hash: switch.data_hash,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Grants `key` the given permission bits, replacing any it already holds (owner only)
+    pub fn grant_role(ctx: Context<ManageRoles>, key: Pubkey, permissions: u8) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            key != owner && key != Pubkey::default(),
+            ErrorCode::InvalidRole
+        );
+        require!(
+            permissions != 0 && permissions & !ALL_PERMISSIONS == 0,
+            ErrorCode::InvalidPermissions
+        );
+
+        let index = switch
+            .roles
+            .iter()
+            .position(|role| role.is_set() && role.key == key)
+            .or_else(|| switch.roles.iter().position(|role| !role.is_set()))
+            .ok_or(ErrorCode::TooManyRoles)?;
+
+        switch.roles[index] = RoleGrant { key, permissions };
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_DELEGATES,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(RoleGranted {
+            switch: switch.key(),
+            key,
+            permissions,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Removes every permission held by `key` (owner only)
+    pub fn revoke_role(ctx: Context<ManageRoles>, key: Pubkey) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let index = switch
+            .roles
+            .iter()
+            .position(|role| role.is_set() && role.key == key)
+            .ok_or(ErrorCode::RoleNotFound)?;
+
+        switch.roles[index] = RoleGrant::default();
+        switch.last_actor = owner;
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_DELEGATES,
+            current_time,
+        )?;
+        bump_seq(switch)?;
+
+        emit!(RoleRevoked {
+            switch: switch.key(),
+            key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets whether pings landing just after the reveal time are still accepted
+    pub fn set_auto_extend_on_late(
+        ctx: Context<SetAutoExtendOnLate>,
+        auto_extend_on_late: bool,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        switch.auto_extend_on_late = auto_extend_on_late;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Caps the switch's total lifetime (owner only)
+    ///
+    /// Once set the cap can only be shortened, never extended or removed.
+    pub fn set_max_lifetime(ctx: Context<SetMaxLifetime>, max_lifetime: i64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            max_lifetime > switch.ping_interval
+                && switch
+                    .max_lifetime
+                    .map_or(true, |current| max_lifetime <= current),
+            ErrorCode::InvalidMaxLifetime
+        );
+        let ends_at = switch
+            .created_at
+            .checked_add(max_lifetime)
+            .ok_or(ErrorCode::TimeOverflow)?;
+        require!(ends_at > current_time, ErrorCode::InvalidMaxLifetime);
+
+        switch.max_lifetime = Some(max_lifetime);
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how long before expiry notify_beneficiary may alert the beneficiary
+    pub fn set_beneficiary_notice(
+        ctx: Context<SetBeneficiaryNotice>,
+        beneficiary_notice: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            beneficiary_notice >= 0 && beneficiary_notice < switch.ping_interval,
+            ErrorCode::InvalidBeneficiaryNotice
+        );
+
+        switch.beneficiary_notice = beneficiary_notice;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Halts or resumes every state-changing instruction, pings included (admin only)
+    pub fn set_global_pause(ctx: Context<SetGlobalPause>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.global_paused = paused;
+
+        emit!(GlobalPauseToggled {
+            admin: *ctx.accounts.admin.key,
+            paused,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Enables or disables minting a soulbound receipt on the first claim
+    pub fn set_mint_receipt(
+        ctx: Context<SetMintReceipt>,
+        mint_receipt: bool,
+        receipt_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            !mint_receipt || receipt_mint.is_some(),
+            ErrorCode::InvalidReceiptMint
+        );
+
+        switch.mint_receipt = mint_receipt;
+        switch.receipt_mint = receipt_mint;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets whether claims need an attestor's published attestation (owner only, before expiry)
+    pub fn set_attestation(
+        ctx: Context<SetAttestation>,
+        attestation_required: bool,
+        attestor: Option<Pubkey>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            !attestation_required || attestor.is_some(),
+            ErrorCode::AttestorRequired
+        );
+
+        switch.attestation_required = attestation_required;
+        switch.attestor = attestor;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Publishes the attestor's on-chain attestation for a switch (configured attestor only)
+    pub fn publish_attestation(ctx: Context<PublishAttestation>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &ctx.accounts.switch;
+        let attestor = ctx.accounts.attestor.key();
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.attestor == Some(attestor), ErrorCode::Unauthorized);
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.switch = switch.key();
+        attestation.attestor = attestor;
+        attestation.timestamp = current_time;
+        attestation.bump = ctx.bumps.attestation;
+
+        Ok(())
+    }
+
+    /// Changes who receives the escrowed tokens on expiry (owner only, while active)
+    ///
+    /// Only the recorded recipient changes; the escrowed balance is left untouched.
+    pub fn reassign_token_beneficiary(
+        ctx: Context<ReassignTokenBeneficiary>,
+        new_beneficiary: Pubkey,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            switch.on_expiry == ON_EXPIRY_RELEASE_TOKENS,
+            ErrorCode::WrongExpiryAction
+        );
+        require!(
+            new_beneficiary != Pubkey::default(),
+            ErrorCode::InvalidTokenBeneficiary
+        );
+
+        let old_beneficiary = switch.token_beneficiary;
+        switch.token_beneficiary = new_beneficiary;
+        switch.last_actor = owner;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(TokenBeneficiaryReassigned {
+            switch: switch.key(),
+            old_beneficiary,
+            new_beneficiary,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Switches between dead-man and auto-keepalive mode (owner only, before expiry)
+    ///
+    /// Either change disarms the switch; leaving keepalive mode restarts the countdown now.
+    pub fn set_mode(ctx: Context<SetMode>, mode: u8) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(mode <= MODE_KEEPALIVE, ErrorCode::InvalidMode);
+
+        // An unarmed keepalive switch may not have been pinged in ages
+        if switch.mode == MODE_KEEPALIVE && mode == MODE_DEAD_MAN && !is_awaiting_activation(switch)
+        {
+            switch.last_ping = current_time;
+        }
+        switch.mode = mode;
+        switch.armed_at = 0;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Arms a keepalive switch so it expires once the interval lapses (owner only)
+    pub fn arm(ctx: Context<ArmSwitch>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(switch.mode == MODE_KEEPALIVE, ErrorCode::NotKeepalive);
+        require!(switch.armed_at == 0, ErrorCode::AlreadyArmed);
+
+        // The interval runs from the moment of arming
+        switch.armed_at = current_time;
+        switch.last_ping = current_time;
+        switch.last_actor = owner;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchArmed {
+            switch: switch.key(),
+            owner,
+            expiration_time: expiration_time(switch, current_time)
+                .ok_or(ErrorCode::TimeOverflow)?,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Appends a chunk of a state export to the owner's import buffer for `id`
+    ///
+    /// Exports exceed the transaction size limit, so they are staged in order before
+    /// `import_state` reads them.
+    pub fn stage_import(
+        ctx: Context<StageImport>,
+        _id: u64,
+        offset: u32,
+        chunk: Vec<u8>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let buffer = &mut ctx.accounts.buffer;
+        if buffer.owner == Pubkey::default() {
+            buffer.owner = *ctx.accounts.owner.key;
+            buffer.bump = ctx.bumps.buffer;
+        }
+
+        require!(
+            offset as usize == buffer.blob.len(),
+            ErrorCode::ImportOutOfOrder
+        );
+        require!(
+            buffer.blob.len() + chunk.len() <= ImportBuffer::CAPACITY,
+            ErrorCode::ExportTooLarge
+        );
+        buffer.blob.extend_from_slice(&chunk);
+
+        Ok(())
+    }
+
+    /// Recreates a switch on a fresh PDA from a staged export (owner only)
+    ///
+    /// The export must be in the current format, belong to the signer and pass the same
+    /// bounds as the setters. Settlement and claim progress starts over and timestamps are
+    /// capped at the current time; the buffer is closed afterwards.
+    pub fn import_state(ctx: Context<ImportState>, id: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let blob = &ctx.accounts.buffer.blob;
+        require!(
+            blob.len() >= EXPORT_HEADER_SIZE && blob[..4] == EXPORT_MAGIC,
+            ErrorCode::InvalidExport
+        );
+        require!(
+            blob[4] == EXPORT_VERSION,
+            ErrorCode::UnsupportedExportVersion
+        );
+        let body_length = u32::from_le_bytes([blob[5], blob[6], blob[7], blob[8]]) as usize;
+        let body = &blob[EXPORT_HEADER_SIZE..];
+        require!(body.len() == body_length, ErrorCode::InvalidExport);
+
+        let mut state =
+            DeadManSwitch::deserialize(&mut &body[..]).map_err(|_| ErrorCode::InvalidExport)?;
+        require_keys_eq!(state.owner, owner, ErrorCode::Unauthorized);
+        require!(id > 0, ErrorCode::InvalidSwitchId);
+
+        // An export is untrusted input: it has to meet the same bounds as a new switch
+        sanitize_import(&mut state, current_time)?;
+
+        let fee = charge_creation_fee(
+            &ctx.accounts.config,
+            ctx.accounts.treasury.as_ref(),
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+        )?;
+        register_switch(&mut ctx.accounts.registry, owner, ctx.bumps.registry)?;
+        reserve_id(&mut ctx.accounts.registry, id)?;
+        ctx.accounts
+            .stats
+            .record_created(switch_rent(DeadManSwitch::SPACE)?)?;
+        if !state.active {
+            ctx.accounts.stats.record_deactivated()?;
+        }
+
+        state.switch_id = id;
+        state.creator = owner;
+        state.bump = ctx.bumps.switch;
+        state.last_actor = owner;
+        bump_seq(&mut state)?;
+        ctx.accounts.switch.set_inner(state);
+
+        emit!(SwitchImported {
+            switch: ctx.accounts.switch.key(),
+            owner,
+            switch_id: id,
+            version: EXPORT_VERSION,
+            fee,
+            timestamp: current_time,
+            seq: ctx.accounts.switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Requires at least `min_pings_in_window` pings in the last `window` seconds (owner only)
+    ///
+    /// A count of 0 disables the policy. The ping history must already satisfy a new policy,
+    /// so enabling it never expires the switch on the spot.
+    pub fn set_ping_window(
+        ctx: Context<SetPingWindow>,
+        min_pings_in_window: u8,
+        window: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            min_pings_in_window as usize <= PING_HISTORY_SIZE,
+            ErrorCode::InvalidPingWindow
+        );
+        require!(
+            min_pings_in_window == 0 || (window > 0 && window <= MAX_PING_INTERVAL),
+            ErrorCode::InvalidPingWindow
+        );
+
+        switch.min_pings_in_window = min_pings_in_window;
+        switch.window = window;
+        require!(
+            !is_expired(switch, current_time),
+            ErrorCode::PingWindowNotMet
+        );
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Permanently fixes the ping interval and schedule (owner only, one-way)
+    pub fn lock_interval(ctx: Context<LockInterval>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!switch.interval_locked, ErrorCode::IntervalLocked);
+
+        switch.interval_locked = true;
+        switch.last_actor = owner;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(IntervalLocked {
+            switch: switch.key(),
+            ping_interval: switch.ping_interval,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets or clears the failover beneficiary and its delay (owner only, before expiry)
+    ///
+    /// The failover may claim once `failover_delay` seconds have passed since expiration
+    /// and the beneficiary still hasn't claimed.
+    pub fn set_failover(
+        ctx: Context<SetFailover>,
+        failover_beneficiary: Option<Pubkey>,
+        failover_delay: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            failover_delay >= 0 && failover_delay <= MAX_PING_INTERVAL,
+            ErrorCode::InvalidFailover
+        );
+        require!(
+            failover_beneficiary.is_none() || failover_beneficiary != switch.beneficiary,
+            ErrorCode::InvalidFailover
+        );
+
+        switch.failover_beneficiary = failover_beneficiary;
+        switch.failover_delay = failover_delay;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Derives a 96-bit AEAD nonce for the next payload encryption (read-only)
+    ///
+    /// The nonce is the first 12 bytes of `hash("nonce" || switch || created_at || seq)`.
+    /// Every update bumps `seq`, so each call after a mutation yields a fresh nonce; reusing
+    /// a nonce across `update_data` calls under the same key breaks AEAD confidentiality.
+    pub fn derive_nonce(ctx: Context<DeriveNonce>) -> Result<[u8; NONCE_SIZE]> {
+        let switch = &ctx.accounts.switch;
+
+        let digest = hashv(&[
+            b"nonce",
+            switch.key().as_ref(),
+            &switch.created_at.to_le_bytes(),
+            &switch.seq.to_le_bytes(),
+        ])
+        .to_bytes();
+
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce.copy_from_slice(&digest[..NONCE_SIZE]);
+        Ok(nonce)
+    }
+
+    /// Links the switch to the owner's heartbeat hub, or unlinks it (owner only, before expiry)
+    pub fn set_hub(ctx: Context<SetHub>, use_hub: bool) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+
+        if use_hub {
+            let hub = ctx.accounts.hub.as_ref().ok_or(ErrorCode::HubRequired)?;
+            switch.hub = Some(hub.key());
+        } else if switch.use_hub {
+            // Keep the liveness the hub already proved once it stops counting
+            switch.last_ping = switch.last_ping.max(switch.hub_heartbeat);
+        }
+        switch.use_hub = use_hub;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Records one heartbeat for all of the owner's hub-linked switches
+    ///
+    /// Writable switches in remaining accounts that are linked to this hub receive the
+    /// heartbeat immediately; others pick it up later through `sync_hub`.
+    pub fn ping_hub<'info>(ctx: Context<'_, '_, 'info, 'info, PingHub<'info>>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH_SIZE,
+            ErrorCode::BatchTooLarge
+        );
+
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let hub = &mut ctx.accounts.hub;
+        if hub.owner == Pubkey::default() {
+            hub.owner = owner;
+            hub.bump = ctx.bumps.hub;
+        }
+        hub.last_heartbeat = current_time;
+        let hub_key = hub.key();
+
+        let mut synced: u32 = 0;
+        for info in ctx.remaining_accounts.iter() {
+            if !info.is_writable {
+                continue;
+            }
+            let mut switch = match Account::<DeadManSwitch>::try_from(info) {
+                Ok(switch) => switch,
+                Err(_) => continue,
+            };
+            if switch.owner != owner || !apply_hub_heartbeat(&mut switch, hub_key, current_time) {
+                continue;
+            }
+
+            bump_seq(&mut switch)?;
+            emit!(HubHeartbeatApplied {
+                switch: switch.key(),
+                hub: hub_key,
+                heartbeat: current_time,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+            switch.exit(&crate::ID)?;
+            synced += 1;
+        }
+
+        emit!(HubPinged {
+            hub: hub_key,
+            owner,
+            synced,
+            timestamp: current_time,
+        });
+
+        Ok(())
+    }
+
+    /// Applies the hub's latest heartbeat to a linked switch (anyone)
+    pub fn sync_hub(ctx: Context<SyncHub>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let hub = &ctx.accounts.hub;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            apply_hub_heartbeat(switch, hub.key(), hub.last_heartbeat),
+            ErrorCode::HubHeartbeatNotApplicable
+        );
+        clear_backup_snapshot(ctx.accounts.backup.as_deref_mut());
+
+        switch.last_actor = Pubkey::default();
+        bump_seq(switch)?;
+
+        emit!(HubHeartbeatApplied {
+            switch: switch.key(),
+            hub: hub.key(),
+            heartbeat: hub.last_heartbeat,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Opens a maintenance window that pushes the deadline back by `duration` (owner only)
+    ///
+    /// Maintenance added since the last ping is capped at `MAX_MAINTENANCE_EXTENSION`, so
+    /// back-to-back windows can't stall the switch indefinitely.
+    pub fn enter_maintenance(ctx: Context<EnterMaintenance>, duration: i64) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.active, ErrorCode::SwitchInactive);
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            !switch.in_maintenance || current_time >= switch.maintenance_until,
+            ErrorCode::MaintenanceActive
+        );
+        let maintenance_extension = switch
+            .maintenance_extension
+            .checked_add(duration)
+            .ok_or(ErrorCode::TimeOverflow)?;
+        require!(
+            duration > 0 && maintenance_extension <= MAX_MAINTENANCE_EXTENSION,
+            ErrorCode::InvalidMaintenanceDuration
+        );
+
+        switch.in_maintenance = true;
+        switch.maintenance_until = current_time
+            .checked_add(duration)
+            .ok_or(ErrorCode::TimeOverflow)?;
+        switch.maintenance_extension = maintenance_extension;
+        switch.last_actor = owner;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_MAINTENANCE,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(MaintenanceStarted {
+            switch: switch.key(),
+            duration,
+            until: switch.maintenance_until,
+            expiration_time: expiration_time(switch, current_time)
+                .ok_or(ErrorCode::TimeOverflow)?,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Ends the maintenance window, giving back any unused extension (owner only)
+    pub fn exit_maintenance(ctx: Context<ExitMaintenance>) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let owner = *ctx.accounts.owner.key;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(switch.in_maintenance, ErrorCode::NotInMaintenance);
+
+        let unused = switch.maintenance_until.saturating_sub(current_time).max(0);
+        switch.maintenance_extension = switch.maintenance_extension.saturating_sub(unused).max(0);
+        switch.in_maintenance = false;
+        switch.last_actor = owner;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            owner,
+            LOG_MAINTENANCE,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(MaintenanceEnded {
+            switch: switch.key(),
+            unused,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Commits or clears a Merkle root of additional beneficiaries (owner only, before expiry)
+    pub fn set_beneficiary_root(
+        ctx: Context<SetBeneficiaryRoot>,
+        beneficiary_root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(beneficiary_root != Some([0u8; 32]), ErrorCode::InvalidProof);
+
+        switch.beneficiary_root = beneficiary_root;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the fee a claimer pays to release the data, 0 to make claims free (owner only)
+    pub fn set_claim_fee(
+        ctx: Context<SetClaimFee>,
+        claim_fee: u64,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.config)?;
+
+        let switch = &mut ctx.accounts.switch;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(!is_expired(switch, current_time), ErrorCode::Expired);
+        require!(
+            claim_fee == 0 || fee_recipient != Pubkey::default(),
+            ErrorCode::InvalidFeeRecipient
+        );
+
+        switch.claim_fee = claim_fee;
+        switch.fee_recipient = fee_recipient;
+        switch.last_actor = *ctx.accounts.owner.key;
+
+        append_log(
+            ctx.accounts.log.as_deref_mut(),
+            switch.has_log,
+            *ctx.accounts.owner.key,
+            LOG_SETTINGS,
+            current_time,
+        )?;
+
+        bump_seq(switch)?;
+
+        emit!(SwitchUpdated {
+            switch: switch.key(),
+            actor: *ctx.accounts.owner.key,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        Ok(())
+    }
+}
+
+/// Counts a new switch against the owner's limit, initializing the registry on first use
+fn register_switch(registry: &mut OwnerRegistry, owner: Pubkey, bump: u8) -> Result<()> {
+    if registry.owner == Pubkey::default() {
+        registry.owner = owner;
+        registry.bump = bump;
+    }
+    require!(
+        registry.count < MAX_SWITCHES_PER_OWNER,
+        ErrorCode::TooManySwitches
+    );
+    registry.count = registry
+        .count
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Marks `id` as used so auto-assigned IDs never collide with it
+fn reserve_id(registry: &mut OwnerRegistry, id: u64) -> Result<()> {
+    let next = id.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+    registry.next_id = registry.next_id.max(next);
+    Ok(())
+}
+
+/// Validates creation inputs and initializes every switch field
+fn init_switch(
+    switch: &mut DeadManSwitch,
+    owner: Pubkey,
+    id: u64,
+    ping_interval: i64,
+    encrypted_data: &[u8],
+    bump: u8,
+    current_time: i64,
+) -> Result<()> {
+    // Validate inputs
+    require!(id > 0, ErrorCode::InvalidSwitchId);
+    require!(
+        ping_interval >= MIN_PING_INTERVAL && ping_interval <= MAX_PING_INTERVAL,
+        ErrorCode::InvalidInterval
+    );
+    validate_data(encrypted_data, MAX_DATA_SIZE)?;
+
+    // Initialize account
+    switch.owner = owner;
+    switch.last_ping = current_time;
+    switch.ping_interval = ping_interval;
+    switch.expired = false; // Initialize as active
+    switch.last_counter = 0; // Strict mode not yet used
+    switch.switch_id = id;
+    switch.callback_program = None;
+    switch.callback_triggered = false;
+    switch.beneficiary = None;
+    switch.data_hash = hash(encrypted_data).to_bytes();
+    switch.public_on_expiry = false;
+    switch.revealed = false;
+    switch.session_key = None;
+    switch.session_expires_at = 0;
+    switch.active = true;
+    switch.schedule = [ScheduleTier::default(); SCHEDULE_TIERS];
+    switch.last_actor = owner;
+    switch.claim_attempts = 0;
+    switch.claim_locked_until = 0;
+    switch.has_log = false;
+    switch.grace_period = 0;
+    switch.encryption_scheme = 0;
+    switch.content_type = 0;
+    switch.banked_time = 0;
+    switch.seq = 0; // Creation is always sequence 0
+    switch.creator = owner;
+    switch.pending_owner = None;
+    switch.frozen_hash = None;
+    switch.delegates = [Pubkey::default(); MAX_DELEGATES];
+    switch.delegate_count = 0;
+    switch.witness = None;
+    switch.close_authority = None;
+    switch.oracle = None;
+    switch.oracle_threshold = 0;
+    switch.warn_threshold = 0;
+    switch.keeper_reward = 0;
+    switch.segments = [BeneficiarySegment::default(); MAX_SEGMENTS];
+    switch.claimed_by = None;
+    switch.claimed_at = None;
+    switch.clock_tolerance = 0;
+    switch.companion = None;
+    switch.auto_close_on_claim = false;
+    switch.on_expiry = ON_EXPIRY_BENEFICIARY;
+    switch.proxy = None;
+    switch.proxy_max_until = 0;
+    switch.settling = false;
+    switch.status_byte = switch.status();
+    switch.reveal_delay = 0;
+    switch.reveal_pending = false;
+    switch.emergency_contact = None;
+    switch.reward_decay_window = 0;
+    switch.storage_kind = STORAGE_INLINE;
+    switch.storage_ref = [0u8; STORAGE_REF_SIZE];
+    switch.ping_count = 0;
+    switch.longest_gap = 0;
+    switch.require_activation_ping = false;
+    switch.hash_required = false;
+    switch.snapshot_count = 0;
+    switch.max_data_size = MAX_DATA_SIZE as u16;
+    switch.deactivated_at = 0;
+    switch.close_cooldown = 0;
+    switch.log_level = LOG_LEVEL_NORMAL;
+    switch.light_pinged = false;
+    switch.beneficiary_pubkey = None;
+    switch.stages = [DisclosureStage::default(); DISCLOSURE_STAGES];
+    switch.external_id = 0;
+    switch.sensitivity = 0;
+    switch.claim_confirmations = 0;
+    switch.burned = false;
+    switch.roles = [RoleGrant::default(); MAX_ROLES];
+    switch.auto_extend_on_late = false;
+    switch.last_memo = [0u8; 32];
+    switch.max_lifetime = None;
+    switch.beneficiary_notice = 0;
+    switch.beneficiary_notified = false;
+    switch.prev_data_hash = [0u8; 32];
+    switch.mint_receipt = false;
+    switch.receipt_mint = None;
+    switch.phase = PHASE_ALIVE;
+    switch.attestation_required = false;
+    switch.attestor = None;
+    switch.jurisdiction = [0u8; JURISDICTION_SIZE];
+    switch.token_beneficiary = Pubkey::default();
+    switch.mode = MODE_DEAD_MAN;
+    switch.armed_at = 0;
+    switch.delegate_pings_since_owner = 0;
+    switch.ping_history = [0; PING_HISTORY_SIZE];
+    switch.ping_history_head = 0;
+    switch.min_pings_in_window = 0;
+    switch.window = 0;
+    switch.interval_locked = false;
+    switch.failover_beneficiary = None;
+    switch.failover_delay = 0;
+    switch.failover_activated = false;
+    switch.use_hub = false;
+    switch.hub = None;
+    switch.hub_heartbeat = 0;
+    switch.in_maintenance = false;
+    switch.maintenance_until = 0;
+    switch.maintenance_extension = 0;
+    switch.beneficiary_root = None;
+    switch.claim_fee = 0;
+    switch.fee_recipient = Pubkey::default();
+    switch.confirm_interval = 0;
+    switch.has_backup = false;
+
+    // Copy encrypted data to fixed array
+    switch.encrypted_data = [0u8; MAX_DATA_SIZE];
+    switch.encrypted_data[..encrypted_data.len()].copy_from_slice(encrypted_data);
+    switch.data_length = encrypted_data.len() as u16;
+
+    switch.created_at = current_time;
+    switch.bump = bump;
+
+    check_first_expiration(switch, current_time)
+}
+
+/// Rejects degenerate timing, such as an interval that slipped past validation, that
+/// would produce a switch already expired when created
+fn check_first_expiration(switch: &DeadManSwitch, current_time: i64) -> Result<()> {
+    let first_expiration = expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+    require!(
+        first_expiration > switch.created_at,
+        ErrorCode::InvalidTimestamp
+    );
+    Ok(())
+}
+
+/// Counts a delegate's ping toward MAX_DELEGATE_PINGS, or resets the count on an owner ping
+///
+/// Delegates and PERM_PING role holders can keep the switch alive only so long before the
+/// owner must prove life. Session keys and proxies already expire on their own, so their
+/// pings neither count nor reset.
+fn count_delegate_ping(switch: &mut DeadManSwitch, authority: Pubkey) -> Result<()> {
+    if authority == switch.owner {
+        switch.delegate_pings_since_owner = 0;
+    } else if switch.delegates().contains(&authority)
+        || switch.has_permission(&authority, PERM_PING)
+    {
+        require!(
+            switch.delegate_pings_since_owner < MAX_DELEGATE_PINGS,
+            ErrorCode::OwnerPingRequired
+        );
+        switch.delegate_pings_since_owner += 1;
+    }
+    Ok(())
+}
+
+/// Validates and applies a ping, returning the next required ping time
+fn record_ping(
+    switch: &mut Account<DeadManSwitch>,
+    authority: Pubkey,
+    current_time: i64,
+) -> Result<i64> {
+    // Security checks
+    require!(switch.active, ErrorCode::SwitchInactive);
+    require!(
+        switch.is_ping_authority(&authority, current_time),
+        ErrorCode::Unauthorized
+    );
+    // Pings during the reveal delay still cancel the pending reveal
+    require!(
+        !is_revealable(switch, current_time) || accepts_late_ping(switch, current_time),
+        ErrorCode::Expired
+    );
+    require!(
+        current_time >= switch.last_ping,
+        ErrorCode::InvalidTimestamp
+    );
+
+    count_delegate_ping(switch, authority)?;
+
+    // The activation ping starts the countdown, so there is no gap or banked time to settle
+    if !is_countdown_paused(switch) {
+        // Consume whatever banked time was used past the regular deadline
+        let regular_deadline = last_alive(switch)
+            .checked_add(effective_interval(switch, current_time))
+            .and_then(|deadline| deadline.checked_add(switch.grace_period))
+            .and_then(|deadline| deadline.checked_add(switch.maintenance_extension))
+            .ok_or(ErrorCode::TimeOverflow)?;
+        let consumed = current_time
+            .saturating_sub(regular_deadline)
+            .clamp(0, switch.banked_time);
+        switch.banked_time -= consumed;
+
+        switch.longest_gap = switch.longest_gap.max(current_time - switch.last_ping);
+    }
+
+    // Update state
+    switch.ping_count = switch.ping_count.saturating_add(1);
+    switch.last_ping = current_time;
+    restart_countdown(switch, current_time);
+    switch.last_actor = authority;
+    bump_seq(switch)?;
+
+    let old_phase = switch.phase;
+    if old_phase != PHASE_ALIVE {
+        switch.phase = PHASE_ALIVE;
+        emit!(PhaseChanged {
+            switch: switch.key(),
+            old_phase,
+            new_phase: PHASE_ALIVE,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+
+    let new_expiration = expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+
+    Ok(new_expiration)
+}
+
+/// Emits the events for a recorded ping at the switch's log level
+fn emit_ping_events(
+    switch: &Account<DeadManSwitch>,
+    authority: Pubkey,
+    new_expiration: i64,
+    activating: bool,
+    late_by: Option<i64>,
+    memo: Option<[u8; 32]>,
+    current_time: i64,
+) {
+    if switch.log_level > LOG_LEVEL_MINIMAL {
+        emit!(SwitchPinged {
+            owner: switch.owner,
+            switch_key: switch.key(),
+            next_required_ping: new_expiration,
+            timestamp: current_time,
+            seq: switch.seq,
+            memo,
+        });
+    }
+
+    if switch.log_level >= LOG_LEVEL_VERBOSE {
+        emit!(PingDetail {
+            switch: switch.key(),
+            authority,
+            ping_count: switch.ping_count,
+            longest_gap: switch.longest_gap,
+            banked_time: switch.banked_time,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+
+    // Activation and late acceptance are state transitions, so they are reported at every level
+    if activating {
+        emit!(SwitchActivated {
+            switch: switch.key(),
+            authority,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+    if let Some(late_by) = late_by {
+        emit!(LatePingAccepted {
+            switch: switch.key(),
+            authority,
+            late_by,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+}
+
+/// Whether an opted-in switch can still take a ping just past its reveal time
+///
+/// Once anything has settled (the hash is frozen or the data revealed) it is too late.
+fn accepts_late_ping(switch: &DeadManSwitch, current_time: i64) -> bool {
+    switch.auto_extend_on_late
+        && switch.frozen_hash.is_none()
+        && !switch.revealed
+        && !switch.settling
+        && reveal_time(switch, current_time)
+            .and_then(|reveal_at| reveal_at.checked_add(LATE_PING_TOLERANCE))
+            .is_some_and(|deadline| current_time < deadline)
+}
+
+/// Seconds a ping at `current_time` is past the reveal time, or None if it is not late
+fn late_ping_delay(switch: &DeadManSwitch, current_time: i64) -> Option<i64> {
+    if !is_revealable(switch, current_time) {
+        return None;
+    }
+    reveal_time(switch, current_time).map(|reveal_at| current_time.saturating_sub(reveal_at))
+}
+
+/// Whether the countdown has not started because the activation ping is outstanding
+fn is_awaiting_activation(switch: &DeadManSwitch) -> bool {
+    switch.require_activation_ping && switch.last_ping == 0
+}
+
+/// Whether no countdown is running: awaiting activation, or a keepalive switch not yet armed
+fn is_countdown_paused(switch: &DeadManSwitch) -> bool {
+    is_awaiting_activation(switch) || (switch.mode == MODE_KEEPALIVE && switch.armed_at == 0)
+}
+
+/// Checks if a switch is expired
+///
+/// The switch's clock tolerance delays expiry by a few seconds so pings landing right
+/// at the deadline are not lost to validator clock skew. This trades a small safety
+/// margin for robustness; with the default tolerance of 0 the deadline is exact.
+fn is_expired(switch: &DeadManSwitch, current_time: i64) -> bool {
+    if is_countdown_paused(switch) {
+        return false;
+    }
+    tolerant_expiration(switch, current_time).map_or(true, |expiration| current_time >= expiration)
+}
+
+/// The deadline plus the switch's clock tolerance, the moment is_expired flips
+fn tolerant_expiration(switch: &DeadManSwitch, current_time: i64) -> Option<i64> {
+    expiration_time(switch, current_time)?.checked_add(switch.clock_tolerance)
+}
+
+/// Time from which an expired switch may be settled, or None on overflow
+fn reveal_time(switch: &DeadManSwitch, current_time: i64) -> Option<i64> {
+    dead_time(switch, current_time)?.checked_add(switch.reveal_delay)
+}
+
+/// When a missing switch counts as dead: its deadline plus the confirmation interval
+fn dead_time(switch: &DeadManSwitch, current_time: i64) -> Option<i64> {
+    tolerant_expiration(switch, current_time)?.checked_add(switch.confirm_interval)
+}
+
+/// Checks if a switch is expired and its reveal delay has elapsed
+fn is_revealable(switch: &DeadManSwitch, current_time: i64) -> bool {
+    if is_countdown_paused(switch) {
+        return false;
+    }
+    reveal_time(switch, current_time).map_or(true, |reveal_at| current_time >= reveal_at)
+}
+
+/// Computes the current deadline, or None on overflow
+fn expiration_time(switch: &DeadManSwitch, current_time: i64) -> Option<i64> {
+    let mut deadline = last_alive(switch)
+        .checked_add(effective_interval(switch, current_time))?
+        .checked_add(switch.grace_period)?
+        .checked_add(switch.banked_time)?
+        .checked_add(switch.maintenance_extension)?;
+
+    // The lifetime cap holds no matter how recently the switch was pinged
+    if let Some(max_lifetime) = switch.max_lifetime {
+        deadline = deadline.min(switch.created_at.checked_add(max_lifetime)?);
+    }
+
+    // Fewer than K pings in the window once the K-th most recent one falls out of it
+    if switch.min_pings_in_window > 0 {
+        let oldest_needed = recent_ping(switch, switch.min_pings_in_window as usize);
+        deadline = deadline.min(oldest_needed.checked_add(switch.window)?);
+    }
+
+    Some(deadline)
+}
+
+/// Latest proof of life: the last ping, or a later applied hub heartbeat
+fn last_alive(switch: &DeadManSwitch) -> i64 {
+    if switch.use_hub {
+        switch.last_ping.max(switch.hub_heartbeat)
+    } else {
+        switch.last_ping
+    }
+}
+
+/// Returns the `nth` most recent recorded ping time (1-based), or 0 if not recorded
+fn recent_ping(switch: &DeadManSwitch, nth: usize) -> i64 {
+    let slot = (switch.ping_history_head as usize + PING_HISTORY_SIZE - nth) % PING_HISTORY_SIZE;
+    switch.ping_history[slot]
+}
+
+/// Returns the ping interval in force for the switch's current age
+///
+/// After a light ping only the light extension applies, never more than a full interval.
+fn effective_interval(switch: &DeadManSwitch, current_time: i64) -> i64 {
+    let age = current_time.saturating_sub(switch.created_at);
+    let interval = switch
+        .schedule
+        .iter()
+        .take_while(|tier| tier.is_set())
+        .filter(|tier| age >= tier.age_threshold)
+        .last()
+        .map_or(switch.ping_interval, |tier| tier.interval);
+
+    if switch.light_pinged {
+        interval.min(LIGHT_PING_EXTENSION)
+    } else {
+        interval
+    }
+}
+
+/// Validates a schedule: set tiers first, strictly increasing thresholds, bounded intervals
+fn validate_schedule(schedule: &[ScheduleTier; SCHEDULE_TIERS]) -> Result<()> {
+    let mut previous_threshold = 0;
+    let mut unset_seen = false;
+
+    for tier in schedule.iter() {
+        if !tier.is_set() {
+            require!(tier.interval == 0, ErrorCode::InvalidSchedule);
+            unset_seen = true;
+            continue;
+        }
+        require!(!unset_seen, ErrorCode::InvalidSchedule);
+        require!(
+            tier.age_threshold > previous_threshold,
+            ErrorCode::InvalidSchedule
+        );
+        require!(
+            tier.interval >= MIN_PING_INTERVAL && tier.interval <= MAX_PING_INTERVAL,
+            ErrorCode::InvalidInterval
+        );
+        previous_threshold = tier.age_threshold;
+    }
+
+    Ok(())
+}
+
+/// Deserializes a switch from an arbitrary account, returning None if malformed
+fn load_switch(info: &AccountInfo) -> Option<DeadManSwitch> {
+    if info.owner != &crate::ID {
+        return None;
+    }
+    let data = info.try_borrow_data().ok()?;
+    DeadManSwitch::try_deserialize(&mut &data[..]).ok()
+}
+
+/// Computes the expiration status, surfacing overflow as an error
+fn compute_expiration_status(
+    switch: &DeadManSwitch,
+    current_time: i64,
+) -> Result<ExpirationStatus> {
+    // Inactive switches never expire; paused countdowns have no deadline yet
+    if !switch.active || is_countdown_paused(switch) {
+        return Ok(ExpirationStatus::Never);
+    }
+
+    let at = expiration_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?;
+
+    if current_time >= at {
+        Ok(ExpirationStatus::Expired)
+    } else {
+        Ok(ExpirationStatus::Active { at })
+    }
+}
+
+/// Counts a failed claim and starts the lockout once the cap is reached
+fn record_failed_claim(
+    switch: &mut DeadManSwitch,
+    switch_key: Pubkey,
+    current_time: i64,
+) -> Result<()> {
+    switch.claim_attempts = switch
+        .claim_attempts
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    if switch.claim_attempts >= MAX_CLAIM_ATTEMPTS {
+        let locked_until = current_time
+            .checked_add(CLAIM_LOCKOUT)
+            .ok_or(ErrorCode::TimeOverflow)?;
+        switch.claim_locked_until = locked_until;
+        switch.claim_attempts = 0;
+
+        emit!(ClaimLocked {
+            switch: switch_key,
+            locked_until,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+
+    Ok(())
+}
+
+/// Turns away a claim that isn't due, counting it against the claimer's attempts
+///
+/// Strangers are refused without counting, so they can't lock out the beneficiary.
+/// `member` admits a proven member of the committed beneficiary list.
+fn reject_claim(
+    switch: &mut Account<DeadManSwitch>,
+    claimer: Pubkey,
+    member: bool,
+    current_time: i64,
+) -> Result<Vec<u8>> {
+    require!(
+        member || is_plausible_claimer(switch, &claimer),
+        ErrorCode::Unauthorized
+    );
+
+    let switch_key = switch.key();
+    switch.last_actor = claimer;
+    bump_seq(switch)?;
+    record_failed_claim(switch, switch_key, current_time)?;
+
+    emit!(ClaimRejected {
+        switch: switch_key,
+        claimer,
+        attempts: switch.claim_attempts,
+        timestamp: current_time,
+        seq: switch.seq,
+    });
+
+    Ok(Vec::new())
+}
+
+/// Heuristic guard against plaintext: compares Shannon entropy to the maximum for the length
+fn has_sufficient_entropy(data: &[u8]) -> bool {
+    if data.len() < MIN_ENTROPY_CHECK_LEN {
+        return true;
+    }
+
+    let mut histogram = [0u64; 256];
+    for &byte in data {
+        histogram[byte as usize] += 1;
+    }
+
+    // H = log2(n) - sum(c * log2(c)) / n, in 16.16 fixed point
+    let n = data.len() as u64;
+    let weighted: u64 = histogram
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| count * log2_fixed(count))
+        .sum();
+    let entropy = log2_fixed(n).saturating_sub(weighted / n);
+    let max_entropy = log2_fixed(n.min(256));
+
+    entropy * 100 >= max_entropy * MIN_ENTROPY_PERCENT
+}
+
+/// log2(x) in 16.16 fixed point for x >= 1
+fn log2_fixed(x: u64) -> u64 {
+    let integer = 63 - x.leading_zeros() as u64;
+
+    // Normalize into [1, 2) with 32 fractional bits, then square to extract bits
+    let mut y = ((x as u128) << 32) >> integer;
+    let mut fraction = 0u64;
+    for bit in (0..16).rev() {
+        y = (y * y) >> 32;
+        if y >= 2u128 << 32 {
+            y >>= 1;
+            fraction |= 1 << bit;
+        }
+    }
+
+    (integer << 16) | fraction
+}
+
+/// Disarms a switch on behalf of its owner and emits the event
+fn deactivate(
+    switch: &mut DeadManSwitch,
+    stats: &mut GlobalStats,
+    switch_key: Pubkey,
+    current_time: i64,
+) -> Result<()> {
+    switch.active = false;
+    switch.deactivated_at = current_time;
+    switch.last_actor = switch.owner;
+    stats.record_deactivated()?;
+    bump_seq(switch)?;
+
+    emit!(SwitchDeactivated {
+        switch: switch_key,
+        owner: switch.owner,
+        timestamp: current_time,
+        seq: switch.seq,
+        close_allowed_at: close_allowed_at(switch)?,
+    });
+
+    Ok(())
+}
+
+/// Rent-exempt lamports locked by one switch account
+fn switch_rent(space: usize) -> Result<u64> {
+    Ok(Rent::get()?.minimum_balance(space))
+}
+
+/// Appends to the custody log, requiring it whenever the switch has one
+fn append_log(
+    log: Option<&mut Account<SwitchLog>>,
+    has_log: bool,
+    actor: Pubkey,
+    action_code: u8,
+    current_time: i64,
+) -> Result<()> {
+    match log {
+        Some(log) => log.append(actor, action_code, current_time),
+        None => {
+            require!(!has_log, ErrorCode::LogRequired);
+            Ok(())
+        }
+    }
+}
+
+/// Moves lamports out of a program-owned account
+fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
+
+    Ok(())
+}
+
+/// Whether `candidate` is the beneficiary of an expired switch past its reveal delay
+fn is_claimable_by(switch: &DeadManSwitch, candidate: &Pubkey, current_time: i64) -> bool {
+    if switch.beneficiary == Some(*candidate) {
+        return is_revealable(switch, current_time);
+    }
+    switch.failover_beneficiary == Some(*candidate) && is_failover_open(switch, current_time)
+}
+
+/// Whether `candidate` is named anywhere on the switch as someone who may eventually claim
+fn is_plausible_claimer(switch: &DeadManSwitch, candidate: &Pubkey) -> bool {
+    confirmation_bit(switch, candidate).is_some() || switch.failover_beneficiary == Some(*candidate)
+}
+
+/// Whether the failover may claim: the beneficiary hasn't claimed by expiration plus the delay
+fn is_failover_open(switch: &DeadManSwitch, current_time: i64) -> bool {
+    switch.claimed_by.is_none()
+        && is_revealable(switch, current_time)
+        && expiration_time(switch, current_time)
+            .and_then(|expiration| expiration.checked_add(switch.failover_delay))
+            .is_some_and(|opens_at| current_time > opens_at)
+}
+
+/// Shared claim flow; `proof` lets a member of the committed beneficiary list claim
+fn claim<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimData<'info>>,
+    proof: Option<&[[u8; 32]]>,
+) -> Result<Vec<u8>> {
+    require_not_paused(&ctx.accounts.config)?;
+
+    let switch = &mut ctx.accounts.switch;
+    let claimer = *ctx.accounts.claimer.key;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    require!(switch.active, ErrorCode::SwitchInactive);
+    require_not_settling(switch)?;
+    require!(!switch.burned, ErrorCode::DataBurned);
+    require!(
+        switch.on_expiry == ON_EXPIRY_BENEFICIARY,
+        ErrorCode::WrongExpiryAction
+    );
+    require!(
+        current_time >= switch.claim_locked_until,
+        ErrorCode::ClaimLocked
+    );
+
+    // Expired but still cooling off: the owner can ping to cancel, so nothing is
+    // released and the attempt is not counted against the beneficiary
+    if is_expired(switch, current_time) && !is_revealable(switch, current_time) {
+        if !switch.reveal_pending {
+            switch.reveal_pending = true;
+            bump_seq(switch)?;
+
+            emit!(RevealPending {
+                switch: switch.key(),
+                reveal_at: reveal_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?,
+                timestamp: current_time,
+                seq: switch.seq,
+            });
+        }
+
+        return Ok(Vec::new());
+    }
+
+    // List members are admitted by proof rather than a stored key
+    let proven = proof.is_some_and(|proof| {
+        is_revealable(switch, current_time) && is_beneficiary_member(switch, &claimer, proof)
+    });
+    if !proven && !is_claimable_by(switch, &claimer, current_time) {
+        let member = proof.is_some_and(|proof| is_beneficiary_member(switch, &claimer, proof));
+        return reject_claim(switch, claimer, member, current_time);
+    }
+
+    // Only the failover gets past the check above without being the beneficiary or proven
+    let failover_activated =
+        !proven && switch.beneficiary != Some(claimer) && !switch.failover_activated;
+    if failover_activated {
+        switch.failover_activated = true;
+    }
+
+    // Shared gates; a configured witness alone never passes the check above
+    let gates = check_claim_gates(
+        switch,
+        &ClaimGateAccounts {
+            witness: ctx.accounts.witness.as_ref(),
+            companion: ctx.accounts.companion.as_deref(),
+            attestation: ctx.accounts.attestation.as_deref(),
+            config: &ctx.accounts.config,
+            claimer: &ctx.accounts.claimer,
+            fee_recipient: ctx.accounts.fee_recipient.as_ref(),
+            system_program: ctx.accounts.system_program.as_ref(),
+        },
+        current_time,
+    )?;
+
+    switch.claim_attempts = 0;
+    let first_claim = switch.claimed_by.is_none();
+    if first_claim {
+        switch.claimed_by = Some(claimer);
+        switch.claimed_at = Some(current_time);
+    }
+    switch.last_actor = claimer;
+    append_log(
+        ctx.accounts.log.as_deref_mut(),
+        switch.has_log,
+        claimer,
+        LOG_CLAIMED,
+        current_time,
+    )?;
+
+    bump_seq(switch)?;
+    let switch_key = switch.key();
+    freeze_hash(switch, switch_key, current_time);
+
+    if failover_activated {
+        emit!(FailoverActivated {
+            switch: switch_key,
+            failover_beneficiary: claimer,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+
+    gates.emit(switch_key, claimer, current_time, switch.seq);
+
+    if first_claim && switch.mint_receipt {
+        let receipt_mint = ctx
+            .accounts
+            .receipt_mint
+            .as_ref()
+            .ok_or(ErrorCode::ReceiptAccountsRequired)?;
+        let receipt_account = ctx
+            .accounts
+            .receipt_account
+            .as_ref()
+            .ok_or(ErrorCode::ReceiptAccountsRequired)?;
+        let token_program = ctx
+            .accounts
+            .token_program
+            .as_ref()
+            .ok_or(ErrorCode::ReceiptAccountsRequired)?;
+        mint_claim_receipt(
+            switch,
+            &claimer,
+            &receipt_mint.to_account_info(),
+            &receipt_account.to_account_info(),
+            &token_program.to_account_info(),
+        )?;
+
+        emit!(ReceiptMinted {
+            switch: switch_key,
+            beneficiary: claimer,
+            mint: receipt_mint.key(),
+            receipt_account: receipt_account.key(),
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+
+    emit!(DataClaimed {
+        switch: switch.key(),
+        beneficiary: claimer,
+        fee: gates.fee,
+        timestamp: current_time,
+        seq: switch.seq,
+    });
+
+    if let Some(witness) = switch.witness {
+        emit!(ClaimWitnessed {
+            switch: switch.key(),
+            beneficiary: claimer,
+            witness,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+    }
+
+    let data = released_data(switch, current_time)?.to_vec();
+
+    // Auto-close only tears the account down after the data has been copied out
+    if switch.auto_close_on_claim {
+        let owner_info = ctx
+            .accounts
+            .owner
+            .as_ref()
+            .ok_or(ErrorCode::AutoCloseAccountsRequired)?
+            .to_account_info();
+        require_keys_eq!(owner_info.key(), switch.owner, ErrorCode::Unauthorized);
+        let registry = ctx
+            .accounts
+            .registry
+            .as_mut()
+            .ok_or(ErrorCode::AutoCloseAccountsRequired)?;
+        let stats = ctx
+            .accounts
+            .stats
+            .as_mut()
+            .ok_or(ErrorCode::AutoCloseAccountsRequired)?;
+
+        registry.count = registry
+            .count
+            .checked_sub(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        if switch.active {
+            stats.record_deactivated()?;
+        }
+        stats.record_closed(switch_rent(DeadManSwitch::SPACE)?)?;
+
+        let bytes_wiped = wipe_account_data(&switch.to_account_info())?;
+
+        emit!(DataWiped {
+            switch: switch.key(),
+            bytes_wiped,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        emit!(SwitchClosed {
+            switch: switch.key(),
+            owner: switch.owner,
+            closer: claimer,
+            timestamp: current_time,
+            seq: switch.seq,
+        });
+
+        switch.close(owner_info)?;
+    }
+
+    Ok(data)
+}
+
+/// Whether `proof` places `candidate` in the switch's committed beneficiary list
+fn is_beneficiary_member(switch: &DeadManSwitch, candidate: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let root = match switch.beneficiary_root {
+        Some(root) => root,
+        None => return false,
+    };
+
+    let mut node = hashv(&[&[0u8], candidate.as_ref()]).to_bytes();
+    for sibling in proof {
+        let (a, b) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        node = hashv(&[&[1u8], &a, &b]).to_bytes();
+    }
+    node == root
+}
+
+/// Advances the mutation sequence number carried by every switch event
+///
+/// Every mutating instruction goes through here, so it also refreshes the status byte.
+fn bump_seq(switch: &mut DeadManSwitch) -> Result<()> {
+    switch.seq = switch
+        .seq
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    switch.status_byte = switch.status();
+    refresh_cached_expiration(switch, Clock::get()?.unix_timestamp);
+    Ok(())
+}
+
+/// Stores the deadline as of `current_time` so reads can skip recomputing it
+fn refresh_cached_expiration(switch: &mut DeadManSwitch, current_time: i64) {
+    switch.cached_expiration = if !switch.active || is_countdown_paused(switch) {
+        i64::MAX
+    } else {
+        expiration_time(switch, current_time).unwrap_or(i64::MAX)
+    };
+}
+
+/// Locks in the hash of the current data the first time the switch is settled
+fn freeze_hash(switch: &mut DeadManSwitch, switch_key: Pubkey, current_time: i64) {
+    if switch.frozen_hash.is_some() {
+        return;
+    }
+
+    let frozen_hash = hash(switch.get_encrypted_data()).to_bytes();
+    switch.frozen_hash = Some(frozen_hash);
+    switch.status_byte = switch.status();
+
+    emit!(HashFrozen {
+        switch: switch_key,
+        hash: frozen_hash,
+        timestamp: current_time,
+        seq: switch.seq,
+    });
+}
+
+/// Reads the oracle value stored as a little-endian i64 at the start of its data
+fn read_oracle_value(oracle: &AccountInfo) -> Result<i64> {
+    let data = oracle.try_borrow_data()?;
+    let bytes: [u8; 8] = data
+        .get(..8)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(ErrorCode::InvalidOracleData)?;
+    Ok(i64::from_le_bytes(bytes))
+}
+
+/// Zeroes everything after the discriminator, returning the number of bytes wiped
+fn wipe_account_data(info: &AccountInfo) -> Result<u32> {
+    let mut data = info.try_borrow_mut_data()?;
+    let body = &mut data[8..];
+    body.fill(0);
+    Ok(body.len() as u32)
+}
+
+/// Ensures segments are in bounds, non-empty, non-overlapping and held by distinct keys
+fn validate_segments(
+    segments: &[BeneficiarySegment; MAX_SEGMENTS],
+    data_length: u16,
+) -> Result<()> {
+    let set: Vec<&BeneficiarySegment> = segments.iter().filter(|s| s.is_set()).collect();
+
+    for (i, segment) in set.iter().enumerate() {
+        require!(
+            segment.start < segment.end && segment.end <= data_length,
+            ErrorCode::InvalidSegment
+        );
+        for other in &set[i + 1..] {
+            require!(
+                other.beneficiary != segment.beneficiary,
+                ErrorCode::InvalidSegment
+            );
+            require!(
+                segment.end <= other.start || other.end <= segment.start,
+                ErrorCode::InvalidSegment
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Linearly decays `amount` to zero over `window` seconds after `elapsed`
+///
+/// Rounds up so any time left in the window still pays at least one lamport.
+fn decayed_reward(amount: u64, window: i64, elapsed: i64) -> u64 {
+    if window <= 0 {
+        return amount;
+    }
+    let remaining = window.saturating_sub(elapsed.max(0)).max(0) as u128;
+    let window = window as u128;
+    ((amount as u128 * remaining + window - 1) / window) as u64
+}
+
+/// Inline switches carry no content address; off-chain ones must have one
+fn validate_storage_ref(storage_kind: u8, storage_ref: &[u8; STORAGE_REF_SIZE]) -> Result<()> {
+    let has_ref = storage_ref.iter().any(|&byte| byte != 0);
+    match storage_kind {
+        STORAGE_INLINE => require!(!has_ref, ErrorCode::InvalidStorageRef),
+        STORAGE_IPFS | STORAGE_ARWEAVE => require!(has_ref, ErrorCode::InvalidStorageRef),
+        _ => return err!(ErrorCode::InvalidStorageRef),
+    }
+    Ok(())
+}
+
+/// Scores ping reliability from 0 to 100
+///
+/// The longest gap between pings sets the base score, falling linearly to 0 as it
+/// approaches the ping interval. The base is then scaled by confidence, which grows
+/// with the ping count until `LIVENESS_FULL_CONFIDENCE_PINGS`.
+fn liveness_score(switch: &DeadManSwitch) -> u8 {
+    let interval = switch.ping_interval.max(1) as i128;
+    let gap = switch.longest_gap.max(0) as i128;
+    let gap_score = 100 - (gap * 100 / interval).min(100);
+
+    let confidence = switch.ping_count.min(LIVENESS_FULL_CONFIDENCE_PINGS) as i128;
+    (gap_score * confidence / LIVENESS_FULL_CONFIDENCE_PINGS as i128) as u8
+}
+
+/// Transfers the configured creation fee from the owner to the treasury
+fn charge_creation_fee<'info>(
+    config: &Config,
+    treasury: Option<&UncheckedAccount<'info>>,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<u64> {
+    let fee = config.creation_fee;
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    let treasury = treasury.ok_or(ErrorCode::InvalidTreasury)?;
+    require_keys_eq!(treasury.key(), config.treasury, ErrorCode::InvalidTreasury);
+    require!(owner.lamports() >= fee, ErrorCode::InsufficientFunds);
+
+    // A fresh treasury must end up rent-exempt or the transfer is rejected
+    let minimum = Rent::get()?.minimum_balance(treasury.data_len());
+    require!(
+        treasury.lamports().saturating_add(fee) >= minimum,
+        ErrorCode::RentExemptionViolated
+    );
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: owner.to_account_info(),
+                to: treasury.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
+
+    Ok(fee)
+}
+
+/// Transfers the switch's claim fee from the claimer to its fee recipient
+///
+/// Both sides must stay rent-safe: the claimer is either emptied or left rent-exempt,
+/// and the recipient ends up rent-exempt.
+fn charge_claim_fee<'info>(
+    switch: &DeadManSwitch,
+    claimer: &Signer<'info>,
+    fee_recipient: Option<&UncheckedAccount<'info>>,
+    system_program: Option<&Program<'info, System>>,
+) -> Result<u64> {
+    let fee = switch.claim_fee;
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    let fee_recipient = fee_recipient.ok_or(ErrorCode::InvalidFeeRecipient)?;
+    require_keys_eq!(
+        fee_recipient.key(),
+        switch.fee_recipient,
+        ErrorCode::InvalidFeeRecipient
+    );
+    let system_program = system_program.ok_or(ErrorCode::InvalidFeeRecipient)?;
+    require!(claimer.lamports() >= fee, ErrorCode::InsufficientFunds);
+
+    let rent = Rent::get()?;
+    let claimer_left = claimer.lamports() - fee;
+    require!(
+        claimer_left == 0 || claimer_left >= rent.minimum_balance(claimer.data_len()),
+        ErrorCode::RentExemptionViolated
+    );
+    require!(
+        fee_recipient.lamports().saturating_add(fee)
+            >= rent.minimum_balance(fee_recipient.data_len()),
+        ErrorCode::RentExemptionViolated
+    );
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: claimer.to_account_info(),
+                to: fee_recipient.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
+
+    Ok(fee)
+}
+
+/// Checks a payload's size and that it looks like ciphertext
+fn validate_data(encrypted_data: &[u8], max_data_size: usize) -> Result<()> {
+    require!(
+        encrypted_data.len() <= max_data_size,
+        ErrorCode::DataTooLarge
+    );
+    require!(!encrypted_data.is_empty(), ErrorCode::EmptyData);
+    require!(
+        has_sufficient_entropy(encrypted_data),
+        ErrorCode::LowEntropyData
+    );
+    Ok(())
+}
+
+/// Earliest time a deactivated switch may be closed
+fn close_allowed_at(switch: &DeadManSwitch) -> Result<i64> {
+    Ok(switch
+        .deactivated_at
+        .checked_add(switch.close_cooldown)
+        .ok_or(ErrorCode::TimeOverflow)?)
+}
+
+/// Rejects an all-zero beneficiary encryption key
+fn validate_beneficiary_pubkey(beneficiary_pubkey: &Option<[u8; 32]>) -> Result<()> {
+    if let Some(key) = beneficiary_pubkey {
+        require!(*key != [0u8; 32], ErrorCode::InvalidBeneficiaryPubkey);
+    }
+    Ok(())
+}
+
+/// Validates a jurisdiction tag: printable ASCII followed only by zero padding
+fn validate_jurisdiction(jurisdiction: &[u8; JURISDICTION_SIZE]) -> Result<()> {
+    let length = jurisdiction
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(JURISDICTION_SIZE);
+    require!(
+        jurisdiction[..length]
+            .iter()
+            .all(|byte| byte.is_ascii_graphic())
+            && jurisdiction[length..].iter().all(|byte| *byte == 0),
+        ErrorCode::InvalidJurisdiction
+    );
+    Ok(())
+}
+
+/// Validates stages: set stages first, strictly increasing delays and lengths within the data
+fn validate_stages(stages: &[DisclosureStage; DISCLOSURE_STAGES], data_length: u16) -> Result<()> {
+    let mut previous: Option<&DisclosureStage> = None;
+    let mut unset_seen = false;
+
+    for stage in stages.iter() {
+        if !stage.is_set() {
+            require!(stage.delay == 0, ErrorCode::InvalidStages);
+            unset_seen = true;
+            continue;
+        }
+        require!(!unset_seen, ErrorCode::InvalidStages);
+        require!(
+            stage.delay >= 0 && stage.length <= data_length,
+            ErrorCode::InvalidStages
+        );
+        if let Some(previous) = previous {
+            require!(
+                stage.delay > previous.delay && stage.length > previous.length,
+                ErrorCode::InvalidStages
+            );
+        }
+        previous = Some(stage);
+    }
+
+    Ok(())
+}
+
+/// Checks an imported switch against the bounds its setters enforce and resets what an
+/// export can't carry over
+///
+/// Timestamps are capped at `current_time`, so a forged export can't push its liveness
+/// into the future. Settlement, claim and expiry progress starts over on the new
+/// account, as do fields tied to the old account's PDAs.
+fn sanitize_import(state: &mut DeadManSwitch, current_time: i64) -> Result<()> {
+    require!(
+        state.ping_interval >= MIN_PING_INTERVAL && state.ping_interval <= MAX_PING_INTERVAL,
+        ErrorCode::InvalidInterval
+    );
+    validate_schedule(&state.schedule)?;
+    require!(
+        (0..=MAX_BANKED_TIME).contains(&state.banked_time),
+        ErrorCode::InvalidPrepayment
+    );
+    require!(
+        (0..=MAX_GRACE_PERIOD).contains(&state.grace_period),
+        ErrorCode::InvalidGracePeriod
+    );
+    require!(
+        state.max_data_size as usize <= MAX_DATA_SIZE,
+        ErrorCode::InvalidMaxDataSize
+    );
+    require!(
+        state.data_length <= state.max_data_size,
+        ErrorCode::DataTooLarge
+    );
+    if !state.burned {
+        validate_data(state.get_encrypted_data(), state.max_data_size as usize)?;
+        require!(
+            state.data_hash == hash(state.get_encrypted_data()).to_bytes(),
+            ErrorCode::InvalidExport
+        );
+    }
+    validate_segments(&state.segments, state.data_length)?;
+    validate_stages(&state.stages, state.data_length)?;
+    validate_storage_ref(state.storage_kind, &state.storage_ref)?;
+    validate_beneficiary_pubkey(&state.beneficiary_pubkey)?;
+    validate_jurisdiction(&state.jurisdiction)?;
+
+    require!(
+        state.delegate_count as usize <= MAX_DELEGATES
+            && (state.ping_history_head as usize) < PING_HISTORY_SIZE,
+        ErrorCode::InvalidExport
+    );
+    require!(
+        state
+            .delegates()
+            .iter()
+            .all(|delegate| *delegate != state.owner),
+        ErrorCode::InvalidDelegate
+    );
+    for role in state.roles.iter().filter(|role| role.is_set()) {
+        require!(role.key != state.owner, ErrorCode::InvalidRole);
+        require!(
+            role.permissions != 0 && role.permissions & !ALL_PERMISSIONS == 0,
+            ErrorCode::InvalidPermissions
+        );
+    }
+
+    require!(
+        state.warn_threshold >= 0 && state.warn_threshold < state.ping_interval,
+        ErrorCode::InvalidWarnThreshold
+    );
+    require!(
+        state.beneficiary_notice >= 0 && state.beneficiary_notice < state.ping_interval,
+        ErrorCode::InvalidBeneficiaryNotice
+    );
+    require!(
+        state
+            .max_lifetime
+            .map_or(true, |max_lifetime| max_lifetime > state.ping_interval),
+        ErrorCode::InvalidMaxLifetime
+    );
+    require!(
+        state.on_expiry <= ON_EXPIRY_RELEASE_TOKENS,
+        ErrorCode::InvalidExpiryAction
+    );
+    require!(
+        state.log_level <= LOG_LEVEL_VERBOSE,
+        ErrorCode::InvalidLogLevel
+    );
+    require!(
+        (state.sensitivity as usize) < SENSITIVITY_LEVELS,
+        ErrorCode::InvalidSensitivity
+    );
+    require!(state.mode <= MODE_KEEPALIVE, ErrorCode::InvalidMode);
+    require!(
+        (0..=MAX_REVEAL_DELAY).contains(&state.reveal_delay),
+        ErrorCode::InvalidRevealDelay
+    );
+    require!(
+        (0..=MAX_CONFIRM_INTERVAL).contains(&state.confirm_interval),
+        ErrorCode::InvalidConfirmInterval
+    );
+    require!(
+        (0..=MAX_CLOCK_TOLERANCE).contains(&state.clock_tolerance),
+        ErrorCode::InvalidClockTolerance
+    );
+    require!(
+        (0..=MAX_CLOSE_COOLDOWN).contains(&state.close_cooldown),
+        ErrorCode::InvalidCloseCooldown
+    );
+    require!(
+        (0..=MAX_REWARD_DECAY_WINDOW).contains(&state.reward_decay_window),
+        ErrorCode::InvalidDecayWindow
+    );
+    require!(
+        (0..=MAX_MAINTENANCE_EXTENSION).contains(&state.maintenance_extension),
+        ErrorCode::InvalidMaintenanceDuration
+    );
+    require!(
+        state.min_pings_in_window as usize <= PING_HISTORY_SIZE
+            && (state.min_pings_in_window == 0
+                || (state.window > 0 && state.window <= MAX_PING_INTERVAL)),
+        ErrorCode::InvalidPingWindow
+    );
+    require!(
+        (0..=MAX_PING_INTERVAL).contains(&state.failover_delay)
+            && (state.failover_beneficiary.is_none()
+                || state.failover_beneficiary != state.beneficiary),
+        ErrorCode::InvalidFailover
+    );
+    require!(
+        state.claim_fee == 0 || state.fee_recipient != Pubkey::default(),
+        ErrorCode::InvalidFeeRecipient
+    );
+
+    state.created_at = state.created_at.min(current_time);
+    state.last_ping = state.last_ping.min(current_time);
+    state.armed_at = state.armed_at.min(current_time);
+    state.hub_heartbeat = state.hub_heartbeat.min(current_time);
+    state.deactivated_at = state.deactivated_at.min(current_time);
+    for ping in state.ping_history.iter_mut() {
+        *ping = (*ping).min(current_time);
+    }
+    state.session_expires_at = state
+        .session_expires_at
+        .min(current_time.saturating_add(MAX_SESSION_DURATION));
+
+    // An export carries no lamports, so there is no escrow behind an imported reward
+    state.keeper_reward = 0;
+    state.expired = false;
+    state.callback_triggered = false;
+    state.settling = false;
+    state.frozen_hash = None;
+    state.reveal_pending = false;
+    state.revealed = false;
+    state.claim_confirmations = 0;
+    state.claimed_by = None;
+    state.claimed_at = None;
+    state.claim_attempts = 0;
+    state.claim_locked_until = 0;
+    state.beneficiary_notified = false;
+    state.failover_activated = false;
+    state.phase = PHASE_ALIVE;
+    // The log, backup, snapshot and receipt PDAs belong to the old account
+    state.has_log = false;
+    state.has_backup = false;
+    state.snapshot_count = 0;
+    state.receipt_mint = None;
+
+    Ok(())
+}
+
+/// Bytes of the data prefix unlocked by the stages at `current_time`
+///
+/// Switches without stages unlock everything at once; None means no stage is reached yet.
+fn unlocked_length(switch: &DeadManSwitch, current_time: i64) -> Result<Option<u16>> {
+    if !switch.stages[0].is_set() {
+        return Ok(Some(switch.data_length));
+    }
+
+    let revealed_for = current_time
+        .checked_sub(reveal_time(switch, current_time).ok_or(ErrorCode::TimeOverflow)?)
+        .ok_or(ErrorCode::TimeOverflow)?;
+    Ok(switch
+        .stages
+        .iter()
+        .take_while(|stage| stage.is_set())
+        .filter(|stage| revealed_for >= stage.delay)
+        .last()
+        .map(|stage| stage.length))
+}
+
+/// The data prefix the stages have released at `current_time`; fails while none is
+fn released_data(switch: &DeadManSwitch, current_time: i64) -> Result<&[u8]> {
+    let length = unlocked_length(switch, current_time)?.ok_or(ErrorCode::StageLocked)?;
+    Ok(&switch.get_encrypted_data()[..length.min(switch.data_length) as usize])
+}
+
+/// Number of beneficiaries that hold a confirmation bit on the switch
+fn possible_confirmations(switch: &DeadManSwitch) -> u32 {
+    let segments = switch
+        .segments
+        .iter()
+        .filter(|segment| segment.is_set())
+        .count();
+    switch.beneficiary.is_some() as u32 + segments as u32
+}
+
+/// Confirmation bit for a primary or segment beneficiary, None for anyone else
+fn confirmation_bit(switch: &DeadManSwitch, key: &Pubkey) -> Option<u8> {
+    if switch.beneficiary == Some(*key) {
+        return Some(1);
+    }
+    switch
+        .segments
+        .iter()
+        .position(|segment| segment.is_set() && segment.beneficiary == *key)
+        .map(|i| 1 << (i + 1))
+}
+
+/// Accounts a claim path offers to the shared claim gates; gates needing a missing one fail
+struct ClaimGateAccounts<'a, 'info> {
+    witness: Option<&'a Signer<'info>>,
+    companion: Option<&'a Account<'info, DeadManSwitch>>,
+    attestation: Option<&'a Account<'info, Attestation>>,
+    config: &'a Config,
+    claimer: &'a Signer<'info>,
+    fee_recipient: Option<&'a UncheckedAccount<'info>>,
+    system_program: Option<&'a Program<'info, System>>,
+}
+
+/// What the claim gates verified, reported once the claim is recorded
+struct ClaimGatesPassed {
+    attestation: Option<(Pubkey, Pubkey)>, // Attestation account and its attestor
+    fee: u64,                              // Claim fee charged, in lamports
+}
+
+impl ClaimGatesPassed {
+    /// Emits the events for the gates that were verified
+    fn emit(&self, switch: Pubkey, claimer: Pubkey, timestamp: i64, seq: u64) {
+        if let Some((attestation, attestor)) = self.attestation {
+            emit!(AttestationVerified {
+                switch,
+                attestation,
+                attestor,
+                claimer,
+                timestamp,
+                seq,
+            });
+        }
+    }
+}
+
+/// Checks the preconditions every claim path shares beyond who may claim
+///
+/// Companion expiry, witness co-signature, confirmations, the published attestation and
+/// the frozen data hash, then the claim fee once everything else has passed. Each claim
+/// instruction calls this so none of them can bypass a gate or the fee.
+fn check_claim_gates(
+    switch: &Account<DeadManSwitch>,
+    accounts: &ClaimGateAccounts,
+    current_time: i64,
+) -> Result<ClaimGatesPassed> {
+    // Companion mode: the data is only released once both switches have expired
+    if let Some(companion) = switch.companion {
+        let linked = accounts.companion.ok_or(ErrorCode::CompanionRequired)?;
+        require_keys_eq!(linked.key(), companion, ErrorCode::CompanionRequired);
+        require!(
+            is_revealable(linked, current_time),
+            ErrorCode::CompanionNotExpired
+        );
+    }
+
+    if let Some(witness) = switch.witness {
+        let signed = accounts
+            .witness
+            .is_some_and(|signer| signer.key() == witness);
+        require!(signed, ErrorCode::WitnessRequired);
+    }
+
+    require_confirmations(switch, accounts.config)?;
+
+    // Real-world verification: the configured attestor must have published for this switch
+    let attestation = if switch.attestation_required {
+        let attestation = accounts.attestation.ok_or(ErrorCode::AttestationRequired)?;
+        require_keys_eq!(
+            attestation.switch,
+            switch.key(),
+            ErrorCode::AttestationMismatch
+        );
+        require!(
+            switch.attestor == Some(attestation.attestor),
+            ErrorCode::AttestationMismatch
+        );
+        Some((attestation.key(), attestation.attestor))
+    } else {
+        None
+    };
+
+    // Data must not have changed since the switch first entered the expired state
+    if let Some(frozen_hash) = switch.frozen_hash {
+        require!(
+            hash(switch.get_encrypted_data()).to_bytes() == frozen_hash,
+            ErrorCode::DataTampered
+        );
+    }
+
+    let fee = charge_claim_fee(
+        switch,
+        accounts.claimer,
+        accounts.fee_recipient,
+        accounts.system_program,
+    )?;
+
+    Ok(ClaimGatesPassed { attestation, fee })
+}
+
+/// Requires the confirmations the config demands for the switch's sensitivity
+///
+/// The demand is capped at the switch's own confirmers, so raising the policy later can
+/// never leave a switch unclaimable.
+fn require_confirmations(switch: &DeadManSwitch, config: &Config) -> Result<()> {
+    let required = (config.required_confirmations[switch.sensitivity as usize] as u32)
+        .min(possible_confirmations(switch));
+    require!(
+        switch.claim_confirmations.count_ones() >= required,
+        ErrorCode::ThresholdNotMet
+    );
+    Ok(())
+}
+
+/// Validates and stores new encrypted data on behalf of `authority`
+///
+/// Shared by update_data and ping_and_update; returns the new data hash.
+fn replace_data(
+    switch: &mut DeadManSwitch,
+    authority: Pubkey,
+    encrypted_data: &[u8],
+    data_hash: Option<[u8; 32]>,
+    current_time: i64,
+) -> Result<[u8; 32]> {
+    require!(
+        authority == switch.owner || switch.has_permission(&authority, PERM_UPDATE_DATA),
+        ErrorCode::Unauthorized
+    );
+    require!(switch.active, ErrorCode::SwitchInactive);
+    require!(!switch.burned, ErrorCode::DataBurned);
+    require!(!is_expired(switch, current_time), ErrorCode::Expired);
+    validate_data(encrypted_data, switch.max_data_size as usize)?;
+    validate_segments(&switch.segments, encrypted_data.len() as u16)?;
+    validate_stages(&switch.stages, encrypted_data.len() as u16)?;
+
+    let computed_hash = hash(encrypted_data).to_bytes();
+    match data_hash {
+        Some(data_hash) => require!(data_hash == computed_hash, ErrorCode::DataTampered),
+        None => require!(!switch.hash_required, ErrorCode::HashRequired),
+    }
+
+    switch.encrypted_data = [0u8; MAX_DATA_SIZE];
+    switch.encrypted_data[..encrypted_data.len()].copy_from_slice(encrypted_data);
+    switch.data_length = encrypted_data.len() as u16;
+    switch.prev_data_hash = switch.data_hash;
+    switch.data_hash = computed_hash;
+    switch.last_actor = authority;
+
+    Ok(computed_hash)
+}
+
+/// Fails while the admin has paused the program
+fn require_not_paused(config: &Config) -> Result<()> {
+    require!(!config.global_paused, ErrorCode::GloballyPaused);
+    Ok(())
+}
+
+/// Refuses to run while a settlement CPI is in flight on the switch
+fn require_not_settling(switch: &DeadManSwitch) -> Result<()> {
+    require!(!switch.settling, ErrorCode::SettlementInProgress);
+    Ok(())
+}
+
+/// Records a returned callback CPI and clears the in-flight flag it ran under
+fn finish_callback(switch: &mut DeadManSwitch) {
+    switch.settling = false;
+    switch.callback_triggered = true;
+    switch.expired = true;
+}
+
+/// Mints one receipt token to the claimer and freezes it so it cannot be transferred
+///
+/// The mint must name the switch PDA as both mint and freeze authority, and the
+/// receipt account must be the claimer's token account for that mint.
+fn mint_claim_receipt<'info>(
+    switch: &Account<'info, DeadManSwitch>,
+    claimer: &Pubkey,
+    receipt_mint: &AccountInfo<'info>,
+    receipt_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let switch_key = switch.key();
+    require_keys_eq!(
+        token_program.key(),
+        TOKEN_PROGRAM_ID,
+        ErrorCode::InvalidReceiptMint
+    );
+    require!(
+        switch.receipt_mint == Some(receipt_mint.key()),
+        ErrorCode::InvalidReceiptMint
+    );
+
+    {
+        require!(
+            receipt_mint.owner == &TOKEN_PROGRAM_ID,
+            ErrorCode::InvalidReceiptMint
+        );
+        let mint = receipt_mint.try_borrow_data()?;
+        require!(mint.len() == TOKEN_MINT_LEN, ErrorCode::InvalidReceiptMint);
+        // COption<Pubkey> is a 4-byte tag followed by the key
+        let mint_authority_set = mint[0..4] == [1, 0, 0, 0] && mint[4..36] == switch_key.to_bytes();
+        let freeze_authority_set =
+            mint[46..50] == [1, 0, 0, 0] && mint[50..82] == switch_key.to_bytes();
+        require!(
+            mint_authority_set && freeze_authority_set && mint[44] == 0,
+            ErrorCode::InvalidReceiptMint
+        );
+
+        require!(
+            receipt_account.owner == &TOKEN_PROGRAM_ID,
+            ErrorCode::InvalidReceiptAccount
+        );
+        let account = receipt_account.try_borrow_data()?;
+        require!(
+            account.len() == TOKEN_ACCOUNT_LEN
+                && account[0..32] == receipt_mint.key().to_bytes()
+                && account[32..64] == claimer.to_bytes(),
+            ErrorCode::InvalidReceiptAccount
+        );
+    }
+
+    let creator = switch.creator;
+    let id_bytes = switch.switch_id.to_le_bytes();
+    let bump = [switch.bump];
+    let signer_seeds: &[&[u8]] = &[b"switch", creator.as_ref(), &id_bytes, &bump];
+
+    let mut mint_data = vec![TOKEN_IX_MINT_TO];
+    mint_data.extend_from_slice(&1u64.to_le_bytes());
+    invoke_signed(
+        &Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(receipt_mint.key(), false),
+                AccountMeta::new(receipt_account.key(), false),
+                AccountMeta::new_readonly(switch_key, true),
+            ],
+            data: mint_data,
+        },
+        &[
+            receipt_mint.clone(),
+            receipt_account.clone(),
+            switch.to_account_info(),
+            token_program.clone(),
+        ],
+        &[signer_seeds],
+    )?;
+
+    invoke_signed(
+        &Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(receipt_account.key(), false),
+                AccountMeta::new_readonly(receipt_mint.key(), false),
+                AccountMeta::new_readonly(switch_key, true),
+            ],
+            data: vec![TOKEN_IX_FREEZE_ACCOUNT],
+        },
+        &[
+            receipt_account.clone(),
+            receipt_mint.clone(),
+            switch.to_account_info(),
+            token_program.clone(),
+        ],
+        &[signer_seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Liveness phase at `current_time`
+fn current_phase(switch: &DeadManSwitch, current_time: i64) -> u8 {
+    if !is_expired(switch, current_time) {
+        PHASE_ALIVE
+    } else if dead_time(switch, current_time).map_or(true, |dead_at| current_time >= dead_at) {
+        PHASE_DEAD
+    } else {
+        PHASE_MISSING
+    }
+}
+
+/// Applies a hub heartbeat to a switch linked to `hub_key`, returning whether it counted
+///
+/// A heartbeat only counts if the switch was not already revealable when it happened.
+fn apply_hub_heartbeat(switch: &mut DeadManSwitch, hub_key: Pubkey, heartbeat: i64) -> bool {
+    if !switch.active
+        || !switch.use_hub
+        || switch.hub != Some(hub_key)
+        || heartbeat <= switch.hub_heartbeat
+        || is_revealable(switch, heartbeat)
+    {
+        return false;
+    }
+
+    switch.hub_heartbeat = heartbeat;
+    restart_countdown(switch, heartbeat);
+    true
+}
+
+/// Resets everything a proof of life at `alive_at` voids; shared by pings and hub heartbeats
+fn restart_countdown(switch: &mut DeadManSwitch, alive_at: i64) {
+    switch.ping_history[switch.ping_history_head as usize] = alive_at;
+    switch.ping_history_head = ((switch.ping_history_head as usize + 1) % PING_HISTORY_SIZE) as u8;
+    switch.light_pinged = false;
+    switch.claim_confirmations = 0; // Proof of life voids any confirmations
+    switch.beneficiary_notified = false;
+    switch.failover_activated = false;
+    // The new countdown only keeps whatever is left of an open maintenance window
+    switch.maintenance_extension = if switch.in_maintenance {
+        switch.maintenance_until.saturating_sub(alive_at).max(0)
+    } else {
+        0
+    };
+    switch.expired = false; // Reset expiration status
+    switch.reveal_pending = false;
+}
+
+/// Drops a backup's reveal snapshot once the owner has shown signs of life
+fn clear_backup_snapshot(backup: Option<&mut Account<SwitchBackup>>) {
+    if let Some(backup) = backup {
+        backup.expired_at = 0;
+    }
+}
+
+/// Main switch storage account
+#[account]
+pub struct DeadManSwitch {
+    pub owner: Pubkey,                                // Switch owner (32 bytes)
+    pub last_ping: i64,                               // Last ping timestamp (8 bytes)
+    pub ping_interval: i64,                           // Required ping interval (8 bytes)
+    pub encrypted_data: [u8; MAX_DATA_SIZE],          // Encrypted message (512 bytes fixed)
+    pub data_length: u16,                             // Actual data length (2 bytes)
+    pub created_at: i64,                              // Creation timestamp (8 bytes)
+    pub bump: u8,                                     // PDA bump (1 byte)
+    pub expired: bool,                                // Expiration status (1 byte)
+    pub last_counter: u64,                            // Last strict-mode ping counter (8 bytes)
+    pub switch_id: u64,                               // ID used in PDA seeds (8 bytes)
+    pub callback_program: Option<Pubkey>,             // Program invoked on expiry (33 bytes)
+    pub callback_triggered: bool,                     // Whether the callback has run (1 byte)
+    pub beneficiary: Option<Pubkey>, // Key allowed to claim after expiry (33 bytes)
+    pub data_hash: [u8; 32],         // SHA-256 of the encrypted data (32 bytes)
+    pub public_on_expiry: bool,      // Data becomes public on expiry (1 byte)
+    pub revealed: bool,              // Whether the public reveal happened (1 byte)
+    pub session_key: Option<Pubkey>, // Short-lived ping key (33 bytes)
+    pub session_expires_at: i64,     // Session key expiry (8 bytes)
+    pub active: bool,                // Whether the switch is armed (1 byte)
+    pub schedule: [ScheduleTier; SCHEDULE_TIERS], // Age-based interval tiers (64 bytes)
+    pub last_actor: Pubkey,          // Last mutating signer, default for cranks (32 bytes)
+    pub claim_attempts: u8,          // Failed claims since last success (1 byte)
+    pub claim_locked_until: i64,     // Claims locked until this time (8 bytes)
+    pub has_log: bool,               // Whether a custody log is attached (1 byte)
+    pub grace_period: i64,           // Extra time after the interval (8 bytes)
+    pub encryption_scheme: u8,       // Client encryption scheme identifier (1 byte)
+    pub content_type: u8,            // Client payload content type (1 byte)
+    pub banked_time: i64,            // Prepaid time added to the deadline (8 bytes)
+    pub seq: u64,                    // Per-switch mutation sequence number (8 bytes)
+    pub creator: Pubkey,             // Owner at creation, used in PDA seeds (32 bytes)
+    pub pending_owner: Option<Pubkey>, // Proposed new owner (33 bytes)
+    pub frozen_hash: Option<[u8; 32]>, // Data hash locked in at first settlement (33 bytes)
+    pub delegates: [Pubkey; MAX_DELEGATES], // Keys allowed to ping on the owner's behalf (32 * MAX_DELEGATES bytes)
+    pub delegate_count: u8,                 // Number of delegate slots in use (1 byte)
+    pub witness: Option<Pubkey>,            // Key that must co-sign beneficiary claims (33 bytes)
+    pub close_authority: Option<Pubkey>,    // Extra key allowed to close the switch (33 bytes)
+    pub oracle: Option<Pubkey>, // Account whose value must also cross the threshold (33 bytes)
+    pub oracle_threshold: i64,  // Oracle value at or above which the condition is met (8 bytes)
+    pub warn_threshold: i64,    // Lead time before expiry at which monitors should warn (8 bytes)
+    pub keeper_reward: u64, // Lamports escrowed on this account for the revealing keeper (8 bytes)
+    pub segments: [BeneficiarySegment; MAX_SEGMENTS], // Per-beneficiary data slices (36 * MAX_SEGMENTS bytes)
+    pub claimed_by: Option<Pubkey>, // Beneficiary of the first successful claim (33 bytes)
+    pub claimed_at: Option<i64>,    // Time of the first successful claim (9 bytes)
+    pub clock_tolerance: i64, // Copy of the config clock tolerance applied to expiry (8 bytes)
+    pub companion: Option<Pubkey>, // Switch that must also be expired before claims (33 bytes)
+    pub auto_close_on_claim: bool, // Close the account once the data is claimed (1 byte)
+    pub on_expiry: u8,        // Action the switch performs once expired (1 byte)
+    pub proxy: Option<Pubkey>, // Relayer allowed to ping until proxy_max_until (33 bytes)
+    pub proxy_max_until: i64, // Absolute deadline for proxy pings (8 bytes)
+    pub settling: bool,       // Set while a settlement CPI is in flight (1 byte)
+    pub status_byte: u8,      // Packed status bits for memcmp filtering (1 byte)
+    pub reveal_delay: i64,    // Cooling-off period between expiry and settlement (8 bytes)
+    pub reveal_pending: bool, // RevealPending already emitted for this expiry (1 byte)
+    pub emergency_contact: Option<Pubkey>, // Key alerted on expiry, with no claim rights (33 bytes)
+    pub reward_decay_window: i64, // Seconds over which the escrowed reward decays, 0 = no decay (8 bytes)
+    pub storage_kind: u8,         // Where the ciphertext is stored (1 byte)
+    pub storage_ref: [u8; STORAGE_REF_SIZE], // Off-chain content address, zero for inline (64 bytes)
+    pub ping_count: u64,                     // Successful pings since creation (8 bytes)
+    pub longest_gap: i64,                    // Longest time between consecutive pings (8 bytes)
+    pub require_activation_ping: bool, // Countdown waits for the first ping while last_ping is 0 (1 byte)
+    pub hash_required: bool,           // update_data must be given the new data hash (1 byte)
+    pub snapshot_count: u32,           // Snapshots taken, also the next snapshot index (4 bytes)
+    pub max_data_size: u16,            // Write cap only, space is always MAX_DATA_SIZE (2 bytes)
+    pub deactivated_at: i64,           // When the switch was deactivated, 0 while active (8 bytes)
+    pub close_cooldown: i64, // Seconds after deactivation before it can be closed (8 bytes)
+    pub log_level: u8,       // Event verbosity, see LOG_LEVEL_* (1 byte)
+    pub light_pinged: bool, // Last ping was a light ping, so the deadline uses the light extension (1 byte)
+    pub beneficiary_pubkey: Option<[u8; 32]>, // Beneficiary's encryption key for the payload, informational (33 bytes)
+    pub stages: [DisclosureStage; DISCLOSURE_STAGES], // Progressive disclosure of the data prefix (30 bytes)
+    pub external_id: u64, // Owner's own bookkeeping ID, independent of the seed ID (8 bytes)
+    pub sensitivity: u8,  // Level picking the required claim confirmations from config (1 byte)
+    pub claim_confirmations: u8, // Beneficiaries that confirmed: bit 0 primary, bit 1 + i segment i (1 byte)
+    pub burned: bool, // Data was destroyed by the owner, the shell is kept for records (1 byte)
+    pub roles: [RoleGrant; MAX_ROLES], // Keys with individual permissions (33 bytes each)
+    pub auto_extend_on_late: bool, // Accept pings shortly after expiry while nothing has settled (1 byte)
+    pub last_memo: [u8; 32],       // Memo carried by the latest ping_with_memo (32 bytes)
+    pub max_lifetime: Option<i64>, // Seconds after creation at which the switch expires regardless of pings (9 bytes)
+    pub beneficiary_notice: i64, // Lead time before expiry at which the beneficiary is notified, 0 = off (8 bytes)
+    pub beneficiary_notified: bool, // Notice already sent for the current countdown (1 byte)
+    pub prev_data_hash: [u8; 32], // Hash of the data replaced by the last update, zeros before any (32 bytes)
+    pub mint_receipt: bool,       // Mint a soulbound receipt token on the first claim (1 byte)
+    pub receipt_mint: Option<Pubkey>, // Mint for claim receipts, authorities must be this switch (33 bytes)
+    pub phase: u8,                    // Last recorded liveness phase, see PHASE_* (1 byte)
+    pub attestation_required: bool,   // Claims need a published attestation (1 byte)
+    pub attestor: Option<Pubkey>,     // Key whose attestation gates claims (33 bytes)
+    pub cached_expiration: i64, // Deadline as of the last mutation, i64::MAX if none (8 bytes)
+    pub jurisdiction: [u8; JURISDICTION_SIZE], // Intended claim jurisdiction, advisory only (8 bytes)
+    pub token_beneficiary: Pubkey, // Recipient of escrowed tokens, default if unset (32 bytes)
+    pub mode: u8,                  // MODE_DEAD_MAN or MODE_KEEPALIVE (1 byte)
+    pub armed_at: i64,             // When a keepalive switch was armed, 0 if not armed (8 bytes)
+    pub delegate_pings_since_owner: u8, // Delegate pings since the owner last pinged (1 byte)
+    pub ping_history: [i64; PING_HISTORY_SIZE], // Ring buffer of recent pings, 0 if unused (8 each)
+    pub ping_history_head: u8,     // Next ping_history slot to write (1 byte)
+    pub min_pings_in_window: u8,   // Pings required within `window`, 0 disables (1 byte)
+    pub window: i64,               // Sliding window length in seconds (8 bytes)
+    pub interval_locked: bool,     // Interval and schedule can no longer change (1 byte)
+    pub failover_beneficiary: Option<Pubkey>, // Claims if the beneficiary doesn't in time (33 bytes)
+    pub failover_delay: i64, // Seconds after expiration before the failover may claim (8 bytes)
+    pub failover_activated: bool, // FailoverActivated already emitted (1 byte)
+    pub use_hub: bool,       // Hub heartbeats count as pings (1 byte)
+    pub hub: Option<Pubkey>, // Owner's HeartbeatHub (33 bytes)
+    pub hub_heartbeat: i64,  // Latest hub heartbeat applied to this switch (8 bytes)
+    pub in_maintenance: bool, // Maintenance window opened and not yet ended (1 byte)
+    pub maintenance_until: i64, // End of the current maintenance window (8 bytes)
+    pub maintenance_extension: i64, // Maintenance added to the deadline since the last ping (8 bytes)
+    pub beneficiary_root: Option<[u8; 32]>, // Merkle root of extra eligible beneficiaries (33 bytes)
+    pub claim_fee: u64, // Lamports the claimer pays to fee_recipient, 0 for free (8 bytes)
+    pub fee_recipient: Pubkey, // Receives claim fees, e.g. the estate (32 bytes)
+    pub confirm_interval: i64, // Silence after expiry before a missing switch is dead (8 bytes)
+    pub has_backup: bool, // A backup PDA mirrors the data, so burns must wipe it too (1 byte)
+}
+
+impl DeadManSwitch {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8
+        + 32
+        + 8
+        + 8
+        + MAX_DATA_SIZE
+        + 2
+        + 8
+        + 1
+        + 1
+        + 8
+        + 8
+        + 33
+        + 1
+        + 65
+        + 2
+        + 41
+        + 1
+        + 16 * SCHEDULE_TIERS
+        + 32
+        + 9
+        + 1
+        + 10
+        + 8
+        + 8
+        + 32
+        + 33
+        + 33
+        + 32 * MAX_DELEGATES
+        + 1
+        + 33
+        + 33
+        + 33
+        + 8
+        + 8
+        + 8
+        + 36 * MAX_SEGMENTS
+        + 33
+        + 9
+        + 8
+        + 33
+        + 1
+        + 1
+        + 33
+        + 8
+        + 1
+        + 1
+        + 8
+        + 1
+        + 33
+        + 8
+        + 1
+        + STORAGE_REF_SIZE
+        + 8
+        + 8
+        + 1
+        + 1
+        + 4
+        + 2
+        + 8
+        + 8
+        + 1
+        + 1
+        + 33
+        + 10 * DISCLOSURE_STAGES
+        + 8
+        + 1
+        + 1
+        + 1
+        + 33 * MAX_ROLES
+        + 1
+        + 32
+        + 9
+        + 8
+        + 1
+        + 32
+        + 1
+        + 33
+        + 1
+        + 1
+        + 33
+        + 8
+        + JURISDICTION_SIZE
+        + 32
+        + 1
+        + 8
+        + 1
+        + 8 * PING_HISTORY_SIZE
+        + 1
+        + 1
+        + 8
+        + 1
+        + 33
+        + 8
+        + 1
+        + 1
+        + 33
+        + 8
+        + 1
+        + 8
+        + 8
+        + 33
+        + 8
+        + 32
+        + 8
+        + 1;
+
+    /// Byte offset of `status_byte` from the start of the account data, discriminator
+    /// included. Fields are only ever appended, so this never moves and clients can
+    /// `memcmp`-filter getProgramAccounts on it (e.g. `STATUS_ACTIVE` alone for live,
+    /// unsettled switches).
+    pub const STATUS_OFFSET: usize = 1620;
+
+    /// Get the actual encrypted data as a slice
+    pub fn get_encrypted_data(&self) -> &[u8] {
+        &self.encrypted_data[..self.data_length as usize]
+    }
+
+    /// Packs the filterable status flags into a single byte
+    pub fn status(&self) -> u8 {
+        let mut status = 0;
+        if self.active {
+            status |= STATUS_ACTIVE;
+        }
+        if self.revealed {
+            status |= STATUS_REVEALED;
+        }
+        if self.frozen_hash.is_some() {
+            status |= STATUS_FROZEN;
+        }
+        status
+    }
+
+    /// Whether `key` may ping: the owner, a delegate, or an unexpired session key or proxy
+    pub fn is_ping_authority(&self, key: &Pubkey, current_time: i64) -> bool {
+        if *key == self.owner {
+            return true;
+        }
+        if self.delegates().contains(key) || self.has_permission(key, PERM_PING) {
+            return true;
+        }
+        if self.proxy == Some(*key) && current_time < self.proxy_max_until {
+            return true;
+        }
+        self.session_key == Some(*key) && current_time < self.session_expires_at
+    }
+
+    /// Whether `key` holds a role granting `permission`
+    pub fn has_permission(&self, key: &Pubkey, permission: u8) -> bool {
+        self.roles
+            .iter()
+            .any(|role| role.is_set() && role.key == *key && role.permissions & permission != 0)
+    }
+
+    /// Returns the delegate keys currently in use
+    pub fn delegates(&self) -> &[Pubkey] {
+        &self.delegates[..self.delegate_count as usize]
+    }
+}
+
+/// Switch information struct for client responses
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwitchInfo {
+    pub owner: Pubkey,
+    pub expired: bool,
+    pub last_ping: i64,
+    pub ping_interval: i64,
+    pub created_at: i64,
+    pub expiration_time: i64,
+    pub current_time: i64,
+    pub expiration_status: ExpirationStatus,
+    pub last_actor: Pubkey,
+    pub warn_threshold: i64,
+    pub on_expiry: u8,
+    pub storage_kind: u8,
+    pub storage_ref: [u8; STORAGE_REF_SIZE],
+    pub max_data_size: u16,
+    pub beneficiary_pubkey: Option<[u8; 32]>,
+    pub external_id: u64,
+    pub max_lifetime: Option<i64>,
+    pub jurisdiction: [u8; JURISDICTION_SIZE],
+}
+
+/// Current data hash and the hash it replaced
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DataChain {
+    pub data_hash: [u8; 32],
+    pub prev_data_hash: [u8; 32],
+}
+
+/// First successful claim of a switch's data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimRecord {
+    pub claimed_by: Option<Pubkey>,
+    pub claimed_at: Option<i64>,
+}
+
+/// Expiration state distinguishing far-future expiry from "never"
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExpirationStatus {
+    Active { at: i64 }, // Armed, expires at the given timestamp
+    Never,              // Deactivated, will never expire
+    Expired,            // Deadline has passed
+}
+
+/// Redundant copy of a switch's payload, released from its own reveal-time snapshot
+#[account]
+pub struct SwitchBackup {
+    pub switch: Pubkey,                      // Primary switch address (32 bytes)
+    pub owner: Pubkey,                       // Switch owner (32 bytes)
+    pub beneficiary: Option<Pubkey>,         // Beneficiary snapshot (33 bytes)
+    pub encrypted_data: [u8; MAX_DATA_SIZE], // Encrypted message copy (512 bytes fixed)
+    pub data_length: u16,                    // Actual data length (2 bytes)
+    pub data_hash: [u8; 32],                 // SHA-256 of the encrypted data (32 bytes)
+    pub expired_at: i64,                     // Reveal time snapshot, 0 until synced (8 bytes)
+    pub bump: u8,                            // PDA bump (1 byte)
+    pub burned: bool,                        // Data was destroyed along with the primary's (1 byte)
+}
+
+impl SwitchBackup {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 32 + 33 + MAX_DATA_SIZE + 2 + 32 + 8 + 1 + 1;
+
+    /// Get the actual encrypted data as a slice
+    pub fn get_encrypted_data(&self) -> &[u8] {
+        &self.encrypted_data[..self.data_length as usize]
+    }
+}
+
+/// Point-in-time copy of a switch's state; never modified after creation
+#[account]
+pub struct SwitchSnapshot {
+    pub switch: Pubkey,      // Switch account address (32 bytes)
+    pub index: u32,          // Snapshot index within the switch (4 bytes)
+    pub owner: Pubkey,       // Owner at snapshot time (32 bytes)
+    pub last_ping: i64,      // Last ping at snapshot time (8 bytes)
+    pub ping_interval: i64,  // Ping interval at snapshot time (8 bytes)
+    pub data_hash: [u8; 32], // Data hash at snapshot time (32 bytes)
+    pub timestamp: i64,      // When the snapshot was taken (8 bytes)
+    pub bump: u8,            // PDA bump (1 byte)
+}
+
+impl SwitchSnapshot {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 4 + 32 + 8 + 8 + 32 + 8 + 1;
+}
+
+/// An attestor's statement that a switch's real-world condition has been verified
+#[account]
+pub struct Attestation {
+    pub switch: Pubkey,   // Switch the attestation refers to (32 bytes)
+    pub attestor: Pubkey, // Key that published it (32 bytes)
+    pub timestamp: i64,   // Publication time (8 bytes)
+    pub bump: u8,         // PDA bump (1 byte)
+}
+
+impl Attestation {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+/// Staging area for a state export being imported in chunks
+#[account]
+pub struct ImportBuffer {
+    pub owner: Pubkey, // Owner importing the export (32 bytes)
+    pub blob: Vec<u8>, // Export bytes staged so far (4 + CAPACITY bytes)
+    pub bump: u8,      // PDA bump (1 byte)
+}
+
+impl ImportBuffer {
+    /// Largest export that can be staged: the header plus a base-layout switch
+    pub const CAPACITY: usize = EXPORT_HEADER_SIZE + DeadManSwitch::SPACE - 8;
+
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 4 + Self::CAPACITY + 1;
+}
+
+/// One heartbeat shared by every switch an owner links to it
+#[account]
+pub struct HeartbeatHub {
+    pub owner: Pubkey,       // Hub owner (32 bytes)
+    pub last_heartbeat: i64, // Latest ping_hub time (8 bytes)
+    pub bump: u8,            // PDA bump (1 byte)
+}
+
+impl HeartbeatHub {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 8 + 1;
+}
+
+/// Per-owner counter limiting how many switches can exist at once
+#[account]
+pub struct OwnerRegistry {
+    pub owner: Pubkey, // Registry owner (32 bytes)
+    pub count: u32,    // Live switch count (4 bytes)
+    pub bump: u8,      // PDA bump (1 byte)
+    pub next_id: u64,  // Lowest switch ID never used by this owner (8 bytes)
+}
+
+impl OwnerRegistry {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 4 + 1 + 8;
+
+    /// ID the next auto-assigned switch will use; 0 is never a valid switch ID
+    pub fn next_auto_id(&self) -> u64 {
+        self.next_id.max(1)
+    }
+}
+
+/// One step of an age-based interval schedule
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScheduleTier {
+    pub age_threshold: i64, // Switch age at which this tier applies, 0 = unset
+    pub interval: i64,      // Ping interval once the threshold is reached
+}
+
+impl ScheduleTier {
+    /// Whether this tier is in use
+    pub fn is_set(&self) -> bool {
+        self.age_threshold > 0
+    }
+}
+
+/// Half-open byte range `[start, end)` of the data a beneficiary may claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BeneficiarySegment {
+    pub beneficiary: Pubkey, // Key allowed to claim the range, default = unset
+    pub start: u16,          // First byte of the range
+    pub end: u16,            // One past the last byte of the range
+}
+
+impl BeneficiarySegment {
+    /// Whether this slot is in use
+    pub fn is_set(&self) -> bool {
+        self.beneficiary != Pubkey::default()
+    }
+}
+
+/// Key holding a set of permission bits on a switch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RoleGrant {
+    pub key: Pubkey,     // Key holding the role, default = unset
+    pub permissions: u8, // PERM_* bits
+}
+
+impl RoleGrant {
+    /// Whether this slot is in use
+    pub fn is_set(&self) -> bool {
+        self.key != Pubkey::default()
+    }
+}
+
+/// Data prefix that becomes claimable `delay` seconds after the switch is revealable
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisclosureStage {
+    pub delay: i64,  // Seconds after the reveal time
+    pub length: u16, // Cumulative bytes unlocked, 0 = unset
+}
+
+impl DisclosureStage {
+    /// Whether this slot is in use
+    pub fn is_set(&self) -> bool {
+        self.length > 0
+    }
+}
+
+/// Append-only custody record for a switch
+#[account]
+pub struct SwitchLog {
+    pub switch: Pubkey,                       // Switch account address (32 bytes)
+    pub len: u16,                             // Entries written (2 bytes)
+    pub entries: [LogEntry; MAX_LOG_ENTRIES], // Fixed-size entry storage (41 bytes each)
+    pub bump: u8,                             // PDA bump (1 byte)
+}
+
+impl SwitchLog {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 2 + LogEntry::SIZE * MAX_LOG_ENTRIES + 1;
+
+    /// Get the written entries as a slice
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries[..self.len as usize]
+    }
+
+    /// Appends an entry, refusing to overwrite once full
+    pub fn append(&mut self, actor: Pubkey, action_code: u8, timestamp: i64) -> Result<()> {
+        let index = self.len as usize;
+        require!(index < MAX_LOG_ENTRIES, ErrorCode::LogFull);
+
+        self.entries[index] = LogEntry {
+            actor,
+            action_code,
+            timestamp,
+        };
+        self.len += 1;
+
+        Ok(())
+    }
+}
+
+/// One custody log record
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LogEntry {
+    pub actor: Pubkey,   // Signer, or default for permissionless cranks
+    pub action_code: u8, // One of the LOG_* action codes
+    pub timestamp: i64,  // Action timestamp
+}
+
+impl LogEntry {
+    /// Serialized size in bytes
+    pub const SIZE: usize = 32 + 1 + 8;
+}
+
+/// Program-wide settings controlled by the admin
+#[account]
+pub struct Config {
+    pub admin: Pubkey,                                    // Program admin (32 bytes)
+    pub bump: u8,                                         // PDA bump (1 byte)
+    pub max_delegates: u8,                                // Delegates allowed per switch (1 byte)
+    pub clock_tolerance: i64, // Seconds past the deadline before a switch counts as expired (8 bytes)
+    pub treasury: Pubkey,     // Receives creation fees (32 bytes)
+    pub creation_fee: u64,    // Lamports charged per switch creation, 0 = free (8 bytes)
+    pub required_confirmations: [u8; SENSITIVITY_LEVELS], // Claim confirmations per sensitivity level (4 bytes)
+    pub global_paused: bool, // Circuit breaker halting every state change (1 byte)
+}
+
+impl Config {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 1 + 1 + 8 + 32 + 8 + SENSITIVITY_LEVELS + 1;
+}
+
+/// Shared pool funding keeper rewards for reveals
+#[account]
+pub struct RewardPool {
+    pub balance: u64, // Lamports available for rewards, excluding rent (8 bytes)
+    pub reward_per_reveal: u64, // Lamports paid per reveal (8 bytes)
+    pub bump: u8,     // PDA bump (1 byte)
+}
+
+impl RewardPool {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 8 + 8 + 1;
+}
+
+/// Reusable default settings for new switches
+#[account]
+pub struct Template {
+    pub owner: Pubkey,         // Template owner (32 bytes)
+    pub template_id: u64,      // ID used in PDA seeds (8 bytes)
+    pub ping_interval: i64,    // Default ping interval (8 bytes)
+    pub grace_period: i64,     // Default grace period (8 bytes)
+    pub encryption_scheme: u8, // Default encryption scheme (1 byte)
+    pub content_type: u8,      // Default content type (1 byte)
+    pub bump: u8,              // PDA bump (1 byte)
+}
+
+impl Template {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 8 + 8 + 8 + 1 + 1 + 1;
+}
+
+/// Per-owner fallback settings for create_switch
+#[account]
+pub struct OwnerDefaults {
+    pub owner: Pubkey,         // Defaults owner (32 bytes)
+    pub ping_interval: i64,    // Default ping interval (8 bytes)
+    pub grace_period: i64,     // Default grace period (8 bytes)
+    pub warn_threshold: i64,   // Default warning lead time (8 bytes)
+    pub encryption_scheme: u8, // Default encryption scheme (1 byte)
+    pub bump: u8,              // PDA bump (1 byte)
+}
+
+impl OwnerDefaults {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 32 + 8 + 8 + 8 + 1 + 1;
+}
+
+/// Program-wide switch counters for dashboards
+#[account]
+pub struct GlobalStats {
+    pub total_created: u64,     // Switches ever created (8 bytes)
+    pub active_count: u64,      // Switches currently active (8 bytes)
+    pub total_rent_locked: u64, // Lamports locked in open switch accounts (8 bytes)
+    pub bump: u8,               // PDA bump (1 byte)
+}
+
+impl GlobalStats {
+    /// Account size including the 8-byte discriminator
+    pub const SPACE: usize = 8 + 8 + 8 + 8 + 1;
+
+    /// Counts a newly created, active switch
+    pub fn record_created(&mut self, rent: u64) -> Result<()> {
+        self.total_created = self
+            .total_created
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.active_count = self
+            .active_count
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_rent_locked = self
+            .total_rent_locked
+            .checked_add(rent)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Counts a switch leaving the active state
+    pub fn record_deactivated(&mut self) -> Result<()> {
+        self.active_count = self
+            .active_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Releases the rent of a closed switch
+    pub fn record_closed(&mut self, rent: u64) -> Result<()> {
+        self.total_rent_locked = self
+            .total_rent_locked
+            .checked_sub(rent)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+// ===== Account Validation Structs ===== //
+
+#[derive(Accounts)]
+#[instruction(
+    id: u64,
+    ping_interval: i64,
+    encrypted_data: Vec<u8>,
+    close_authority: Option<Pubkey>,
+    warn_threshold: i64,
+    segments: [BeneficiarySegment; MAX_SEGMENTS],
+    on_expiry: u8,
+    storage_kind: u8,
+    storage_ref: [u8; STORAGE_REF_SIZE],
+    max_data_size: u16,
+    log_level: u8,
+    beneficiary_pubkey: Option<[u8; 32]>
+)]
+pub struct CreateSwitch<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = DeadManSwitch::SPACE,
+        seeds = [b"switch", owner.key.as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OwnerRegistry::SPACE,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Receives the creation fee; validated against the config treasury
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [b"defaults", owner.key.as_ref()],
+        bump = defaults.bump,
+    )]
+    pub defaults: Option<Account<'info, OwnerDefaults>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSwitchAutoId<'info> {
+    // Declared before the switch so its next ID is loaded for the switch seeds
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OwnerRegistry::SPACE,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init,
+        payer = owner,
+        space = DeadManSwitch::SPACE,
+        seeds = [b"switch", owner.key.as_ref(), &registry.next_auto_id().to_le_bytes()],
+        bump
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Receives the creation fee; validated against the config treasury
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Ping<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    // Owner or active session key, checked in the instruction
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        mut,
+        seeds = [b"backup", switch.key().as_ref()],
+        bump = backup.bump,
+    )]
+    pub backup: Option<Box<Account<'info, SwitchBackup>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetCallbackProgram<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct TriggerCallback<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    /// CHECK: Validated against the stored callback program in the instruction
+    #[account(executable)]
+    pub callback_program: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct MarkExpired<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct NotifyBeneficiary<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct GetPhase<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePhase<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct CheckExpiration<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct ShouldWarn<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct WouldBeExpired<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct GetLivenessScore<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct CheckExpirationWithOracle<'info> {
+    #[account(
+        constraint = switch.oracle.is_some() @ ErrorCode::NoOracle,
+        constraint = switch.oracle == Some(oracle.key()) @ ErrorCode::InvalidOracle,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    /// CHECK: Matched against the oracle stored on the switch; only its data is read
+    pub oracle: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetSwitchInfo<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct GetRawState<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct SetBeneficiary<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetWitness<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetCloseAuthority<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetOracle<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetCompanion<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoCloseOnClaim<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRevealDelay<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetConfirmInterval<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmergencyContact<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRequireActivationPing<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateBackup<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init,
+        payer = owner,
+        space = SwitchBackup::SPACE,
+        seeds = [b"backup", switch.key().as_ref()],
+        bump
+    )]
+    pub backup: Account<'info, SwitchBackup>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SyncBackup<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        mut,
+        seeds = [b"backup", switch.key().as_ref()],
+        bump = backup.bump,
+    )]
+    pub backup: Account<'info, SwitchBackup>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimFromBackup<'info> {
+    pub backup: Account<'info, SwitchBackup>,
+    pub beneficiary: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpRent<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct RentStatus<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct EstimateRent {}
+
+#[derive(Accounts)]
+pub struct CloseSwitch<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+        constraint = closer.key() == switch.owner
+            || switch.close_authority == Some(closer.key()) @ ErrorCode::Unauthorized,
+        close = owner,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        mut,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    /// CHECK: Receives the rent refund; validated against the switch owner
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    pub closer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSwitch<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        mut,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [b"backup", switch.key().as_ref()],
+        bump = backup.bump,
+    )]
+    pub backup: Option<Box<Account<'info, SwitchBackup>>>,
+}
+
+#[derive(Accounts)]
+pub struct CheckExpirationBatch {}
+
+#[derive(Accounts)]
+pub struct FindExpiring {}
+
+#[derive(Accounts)]
+pub struct SetPublicOnExpiry<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ReadPublic<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub reader: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct GrantSession<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct AuthorizeProxy<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeProxy<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct DeactivateSwitch<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct GetExpirationStatus<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct SetSchedule<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct GetEffectiveInterval<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct GetNextDeadline<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct GetCachedExpiration<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_id: u64)]
+pub struct Rotate<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner,
+    )]
+    pub old_switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init,
+        payer = owner,
+        space = DeadManSwitch::SPACE,
+        seeds = [b"switch", owner.key.as_ref(), &new_id.to_le_bytes()],
+        bump
+    )]
+    pub new_switch: Account<'info, DeadManSwitch>,
+    #[account(
+        mut,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Box<Account<'info, OwnerRegistry>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimData<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+    pub witness: Option<Signer<'info>>,
+    pub companion: Option<Box<Account<'info, DeadManSwitch>>>,
+    /// CHECK: Receives the rent refund on auto-close; validated against the switch owner
+    #[account(mut)]
+    pub owner: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [b"registry", switch.owner.as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Option<Box<Account<'info, OwnerRegistry>>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Option<Box<Account<'info, GlobalStats>>>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: Receipt mint; validated against the switch and its authorities in the instruction
+    #[account(mut)]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+    /// CHECK: Claimer's token account for the receipt; validated in the instruction
+    #[account(mut)]
+    pub receipt_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: Validated against the SPL Token program ID in the instruction
+    pub token_program: Option<UncheckedAccount<'info>>,
+    pub attestation: Option<Box<Account<'info, Attestation>>>,
+    /// CHECK: Receives the claim fee; validated against the switch's fee recipient
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSegment<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+    pub witness: Option<Signer<'info>>,
+    pub companion: Option<Box<Account<'info, DeadManSwitch>>>,
+    pub attestation: Option<Box<Account<'info, Attestation>>>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: Receives the claim fee; validated against the switch's fee recipient
+    #[account(mut)]
+    pub fee_recipient: Option<UncheckedAccount<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct GetClaimRecord<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct GetDataChain<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCombined<'info> {
+    #[account(mut)]
+    pub first: Account<'info, DeadManSwitch>,
+    #[account(mut)]
+    pub second: Account<'info, DeadManSwitch>,
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", first.key().as_ref()],
+        bump = first_log.bump,
+    )]
+    pub first_log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        mut,
+        seeds = [b"log", second.key().as_ref()],
+        bump = second_log.bump,
+    )]
+    pub second_log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: Receives the first switch's claim fee; validated against its fee recipient
+    #[account(mut)]
+    pub first_fee_recipient: Option<UncheckedAccount<'info>>,
+    /// CHECK: Receives the second switch's claim fee; validated against its fee recipient
+    #[account(mut)]
+    pub second_fee_recipient: Option<UncheckedAccount<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct DeactivateAll<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAllEligible<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    /// CHECK: Receives the rent refunds; every closed switch must belong to it
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    // Owner or the close authority of each switch, checked per account
+    pub closer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct InitLog<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init,
+        payer = owner,
+        space = SwitchLog::SPACE,
+        seeds = [b"log", switch.key().as_ref()],
+        bump
+    )]
+    pub log: Box<Account<'info, SwitchLog>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ReadLog<'info> {
+    pub log: Box<Account<'info, SwitchLog>>,
+}
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitRewardPool<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = RewardPool::SPACE,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundPool<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPool<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, RewardPool>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Reveal<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    /// CHECK: Receives the decayed part of the escrow; validated against the switch owner
+    #[account(mut, address = switch.owner @ ErrorCode::Unauthorized)]
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, RewardPool>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct EscrowReward<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRewardDecayWindow<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimReward<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: u64)]
+pub struct CreateTemplate<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = Template::SPACE,
+        seeds = [b"template", owner.key.as_ref(), &template_id.to_le_bytes()],
+        bump
+    )]
+    pub template: Account<'info, Template>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateSwitchFromTemplate<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = DeadManSwitch::SPACE,
+        seeds = [b"switch", owner.key.as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OwnerRegistry::SPACE,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, OwnerRegistry>,
+    #[account(has_one = owner @ ErrorCode::Unauthorized)]
+    pub template: Account<'info, Template>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Receives the creation fee; validated against the config treasury
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetOwnerDefaults<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OwnerDefaults::SPACE,
+        seeds = [b"defaults", owner.key.as_ref()],
+        bump
+    )]
+    pub defaults: Account<'info, OwnerDefaults>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct CanClaim<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateInterval<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct InitGlobalStats<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = GlobalStats::SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub stats: Account<'info, GlobalStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetGlobalStats<'info> {
+    #[account(
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Account<'info, GlobalStats>,
+}
+
+#[derive(Accounts)]
+pub struct Prepay<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct TransferOwnership<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        mut,
+        seeds = [b"registry", switch.owner.as_ref()],
+        bump = old_registry.bump,
+    )]
+    pub old_registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init_if_needed,
+        payer = new_owner,
+        space = OwnerRegistry::SPACE,
+        seeds = [b"registry", new_owner.key.as_ref()],
+        bump
+    )]
+    pub new_registry: Account<'info, OwnerRegistry>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+
+    #[account(mut)]
+    pub new_owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOwnershipTransfer<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxDelegates<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetClockTolerance<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCreationFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SyncClockTolerance<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct ManageDelegates<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+}
+
+#[derive(Accounts)]
+pub struct DeriveSwitchAddress {}
+
+#[derive(Accounts)]
+pub struct UpdateData<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct PingAndUpdate<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    // Must be both a ping authority and allowed to update data, checked in the instruction
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [b"backup", switch.key().as_ref()],
+        bump = backup.bump,
+    )]
+    pub backup: Option<Box<Account<'info, SwitchBackup>>>,
+}
+
+#[derive(Accounts)]
+pub struct SetHashRequired<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct TakeSnapshot<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init,
+        payer = owner,
+        space = SwitchSnapshot::SPACE,
+        seeds = [b"snapshot", switch.key().as_ref(), &switch.snapshot_count.to_le_bytes()],
+        bump
+    )]
+    pub snapshot: Account<'info, SwitchSnapshot>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u32)]
+pub struct GetSnapshot<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        seeds = [b"snapshot", switch.key().as_ref(), &index.to_le_bytes()],
+        bump = snapshot.bump,
+    )]
+    pub snapshot: Account<'info, SwitchSnapshot>,
+}
+
+#[derive(Accounts)]
+pub struct SetCloseCooldown<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct GetMyRole<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct SetLogLevel<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetBeneficiaryPubkey<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetStages<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetExternalId<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmClaim<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub confirmer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetSensitivity<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRequiredConfirmations<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BurnData<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    #[account(
+        mut,
+        seeds = [b"backup", switch.key().as_ref()],
+        bump = backup.bump,
+    )]
+    pub backup: Option<Box<Account<'info, SwitchBackup>>>,
+}
+
+#[derive(Accounts)]
+pub struct ManageRoles<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoExtendOnLate<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxLifetime<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetBeneficiaryNotice<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintReceipt<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetAttestation<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct PublishAttestation<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init,
+        payer = attestor,
+        space = Attestation::SPACE,
+        seeds = [b"attestation", switch.key().as_ref(), attestor.key().as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+    #[account(mut)]
+    pub attestor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ReassignTokenBeneficiary<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetMode<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ArmSwitch<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ExportState<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct StageImport<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ImportBuffer::SPACE,
+        seeds = [b"import", owner.key.as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub buffer: Box<Account<'info, ImportBuffer>>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct ImportState<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner,
+        seeds = [b"import", owner.key.as_ref(), &id.to_le_bytes()],
+        bump = buffer.bump,
+    )]
+    pub buffer: Box<Account<'info, ImportBuffer>>,
+    #[account(
+        init,
+        payer = owner,
+        space = DeadManSwitch::SPACE,
+        seeds = [b"switch", owner.key.as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OwnerRegistry::SPACE,
+        seeds = [b"registry", owner.key.as_ref()],
+        bump
+    )]
+    pub registry: Box<Account<'info, OwnerRegistry>>,
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = stats.bump,
+    )]
+    pub stats: Box<Account<'info, GlobalStats>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: Receives the creation fee; validated against the config treasury
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPingWindow<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct LockInterval<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetFailover<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct DeriveNonce<'info> {
+    pub switch: Account<'info, DeadManSwitch>,
+}
+
+#[derive(Accounts)]
+pub struct SetHub<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner @ ErrorCode::Unauthorized,
+        seeds = [b"hub", owner.key.as_ref()],
+        bump = hub.bump,
+    )]
+    pub hub: Option<Account<'info, HeartbeatHub>>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct PingHub<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = HeartbeatHub::SPACE,
+        seeds = [b"hub", owner.key.as_ref()],
+        bump
+    )]
+    pub hub: Account<'info, HeartbeatHub>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SyncHub<'info> {
+    #[account(mut)]
+    pub switch: Account<'info, DeadManSwitch>,
+    #[account(
+        seeds = [b"hub", switch.owner.as_ref()],
+        bump = hub.bump,
+    )]
+    pub hub: Account<'info, HeartbeatHub>,
+    #[account(
+        mut,
+        seeds = [b"backup", switch.key().as_ref()],
+        bump = backup.bump,
+    )]
+    pub backup: Option<Box<Account<'info, SwitchBackup>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct EnterMaintenance<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct ExitMaintenance<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetBeneficiaryRoot<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimFee<'info> {
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub switch: Account<'info, DeadManSwitch>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"log", switch.key().as_ref()],
+        bump = log.bump,
+    )]
+    pub log: Option<Box<Account<'info, SwitchLog>>>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+}
+
+// ===== Events ===== //
+
+#[event]
+pub struct SwitchCreated {
+    pub switch: Pubkey,           // Switch account address
+    pub owner: Pubkey,            // Owner public key
+    pub switch_id: u64,           // Unique switch ID
+    pub ping_interval: i64,       // Ping interval in seconds
+    pub expiration_time: i64,     // Initial expiration timestamp
+    pub timestamp: i64,           // Creation timestamp
+    pub template: Option<Pubkey>, // Template the settings came from, if any
+    pub seq: u64,                 // Switch sequence number after this change
+    pub fee: u64,                 // Creation fee paid to the treasury
+}
+
+#[event]
+pub struct SwitchPinged {
+    pub owner: Pubkey,           // Owner public key
+    pub switch_key: Pubkey,      // Switch account address
+    pub next_required_ping: i64, // Next required ping timestamp
+    pub timestamp: i64,          // Ping timestamp
+    pub seq: u64,                // Switch sequence number after this change
+    pub memo: Option<[u8; 32]>,  // Audit memo, for ping_with_memo
+}
+
+#[event]
+pub struct LightPinged {
+    pub switch: Pubkey,          // Switch account address
+    pub authority: Pubkey,       // Signer of the light ping
+    pub next_required_ping: i64, // Deadline after the light extension
+    pub timestamp: i64,          // Ping timestamp
+    pub seq: u64,                // Switch sequence number after this change
+}
+
+#[event]
+pub struct LatePingAccepted {
+    pub switch: Pubkey,    // Switch account address
+    pub authority: Pubkey, // Signer of the late ping
+    pub late_by: i64,      // Seconds past the reveal time
+    pub timestamp: i64,    // Ping timestamp
+    pub seq: u64,          // Switch sequence number after this change
+}
+
+#[event]
+pub struct PingDetail {
+    pub switch: Pubkey,    // Switch account address
+    pub authority: Pubkey, // Signer of the ping
+    pub ping_count: u64,   // Pings recorded so far
+    pub longest_gap: i64,  // Longest gap between pings so far
+    pub banked_time: i64,  // Banked time left after this ping
+    pub timestamp: i64,    // Ping timestamp
+    pub seq: u64,          // Switch sequence number after this change
+}
+
+#[event]
+pub struct SwitchActivated {
+    pub switch: Pubkey,    // Switch account address
+    pub authority: Pubkey, // Signer of the activation ping
+    pub timestamp: i64,    // Activation timestamp, the start of the countdown
+    pub seq: u64,          // Switch sequence number after this change
+}
+
+#[event]
+pub struct SwitchExpired {
+    pub switch: Pubkey, // Switch account address
+    pub timestamp: i64, // Expiration timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct SwitchExpiredNotification {
+    pub switch: Pubkey,                    // Switch account address
+    pub owner: Pubkey,                     // Owner public key
+    pub beneficiary: Option<Pubkey>,       // Key allowed to claim, if any
+    pub emergency_contact: Option<Pubkey>, // Key to alert, with no claim rights
+    pub timestamp: i64,                    // Expiration timestamp
+    pub seq: u64,                          // Switch sequence number after this change
+}
+
+#[event]
+pub struct BeneficiaryNotified {
+    pub switch: Pubkey,      // Switch account address
+    pub beneficiary: Pubkey, // Beneficiary to alert
+    pub expires_at: i64,     // Deadline the notice refers to
+    pub timestamp: i64,      // Notice timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct PhaseChanged {
+    pub switch: Pubkey, // Switch account address
+    pub old_phase: u8,  // Phase before the change
+    pub new_phase: u8,  // Phase after the change
+    pub timestamp: i64, // Change timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct CallbackTriggered {
+    pub switch: Pubkey,           // Switch account address
+    pub callback_program: Pubkey, // Program that was invoked
+    pub data_length: u16,         // Encrypted data length passed to the callback
+    pub timestamp: i64,           // Trigger timestamp
+    pub seq: u64,                 // Switch sequence number after this change
+}
+
+#[event]
+pub struct BackupCreated {
+    pub switch: Pubkey,      // Switch account address
+    pub backup: Pubkey,      // Backup account address
+    pub data_hash: [u8; 32], // Hash of the mirrored data
+    pub timestamp: i64,      // Backup timestamp
+}
+
+#[event]
+pub struct BackupClaimed {
+    pub switch: Pubkey,      // Primary switch address
+    pub beneficiary: Pubkey, // Claiming beneficiary
+    pub timestamp: i64,      // Claim timestamp
 }
 
-#[derive(Accounts)]
-pub struct CheckExpiration<'info> {
-    pub switch: Account<'info, DeadManSwitch>,
+#[event]
+pub struct RentToppedUp {
+    pub switch: Pubkey, // Switch account address
+    pub payer: Pubkey,  // Account that funded the top-up
+    pub amount: u64,    // Lamports transferred
+    pub timestamp: i64, // Top-up timestamp
+    pub seq: u64,       // Switch sequence number after this change
 }
 
-#[derive(Accounts)]
-pub struct GetSwitchInfo<'info> {
-    pub switch: Account<'info, DeadManSwitch>,
+#[event]
+pub struct SwitchClosed {
+    pub switch: Pubkey, // Switch account address
+    pub owner: Pubkey,  // Owner refunded the rent
+    pub closer: Pubkey, // Owner or close authority that signed
+    pub timestamp: i64, // Close timestamp
+    pub seq: u64,       // Switch sequence number after this change
 }
 
-// ===== Events ===== //
+#[event]
+pub struct SwitchCancelled {
+    pub switch: Pubkey, // Switch account address
+    pub owner: Pubkey,  // Owner refunded the rent
+    pub timestamp: i64, // Cancellation timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
 
 #[event]
-pub struct SwitchCreated {
+pub struct PublicRevealed {
+    pub switch: Pubkey, // Switch account address
+    pub reader: Pubkey, // First reader of the public data
+    pub timestamp: i64, // Reveal timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct SessionGranted {
+    pub switch: Pubkey,      // Switch account address
+    pub session_key: Pubkey, // Authorized session key
+    pub expires_at: i64,     // Session expiry timestamp
+    pub timestamp: i64,      // Grant timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct SessionRevoked {
+    pub switch: Pubkey,      // Switch account address
+    pub session_key: Pubkey, // Revoked session key
+    pub timestamp: i64,      // Revocation timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct ProxyAuthorized {
+    pub switch: Pubkey, // Switch account address
+    pub proxy: Pubkey,  // Authorized relayer
+    pub max_until: i64, // Last moment the proxy may ping
+    pub timestamp: i64, // Grant timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct ProxyRevoked {
+    pub switch: Pubkey, // Switch account address
+    pub proxy: Pubkey,  // Revoked relayer
+    pub timestamp: i64, // Revocation timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct SwitchDeactivated {
+    pub switch: Pubkey,        // Switch account address
+    pub owner: Pubkey,         // Owner public key
+    pub timestamp: i64,        // Deactivation timestamp
+    pub seq: u64,              // Switch sequence number after this change
+    pub close_allowed_at: i64, // Earliest time the switch can be closed
+}
+
+#[event]
+pub struct SwitchRotated {
+    pub old_switch: Pubkey, // Closed switch address
+    pub new_switch: Pubkey, // New switch address
+    pub owner: Pubkey,      // Owner public key
+    pub new_id: u64,        // ID of the new switch
+    pub timestamp: i64,     // Rotation timestamp
+    pub seq: u64,           // Switch sequence number after this change
+}
+
+#[event]
+pub struct DataClaimed {
+    pub switch: Pubkey,      // Switch account address
+    pub beneficiary: Pubkey, // Claiming beneficiary
+    pub fee: u64,            // Claim fee paid, in lamports
+    pub timestamp: i64,      // Claim timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct ClaimWitnessed {
+    pub switch: Pubkey,      // Switch account address
+    pub beneficiary: Pubkey, // Claiming beneficiary
+    pub witness: Pubkey,     // Co-signing witness
+    pub timestamp: i64,      // Claim timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct SegmentClaimed {
+    pub switch: Pubkey,      // Switch account address
+    pub beneficiary: Pubkey, // Claiming beneficiary
+    pub start: u16,          // First byte returned
+    pub end: u16,            // One past the last byte returned
+    pub timestamp: i64,      // Claim timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct CombinedClaimed {
+    pub first: Pubkey,       // First switch address
+    pub second: Pubkey,      // Second switch address
+    pub beneficiary: Pubkey, // Claiming beneficiary
+    pub first_seq: u64,      // First switch sequence number after this change
+    pub second_seq: u64,     // Second switch sequence number after this change
+    pub timestamp: i64,      // Claim timestamp
+}
+
+#[event]
+pub struct ClaimLocked {
+    pub switch: Pubkey,    // Switch account address
+    pub locked_until: i64, // End of the claim cooldown
+    pub timestamp: i64,    // Lockout timestamp
+    pub seq: u64,          // Switch sequence number after this change
+}
+
+#[event]
+pub struct BatchDeactivated {
+    pub owner: Pubkey,  // Owner public key
+    pub count: u32,     // Number of switches deactivated
+    pub timestamp: i64, // Batch timestamp
+}
+
+#[event]
+pub struct BatchClosed {
+    pub owner: Pubkey,  // Owner refunded the rent
+    pub closer: Pubkey, // Owner or close authority that signed
+    pub count: u32,     // Number of switches closed
+    pub lamports: u64,  // Total lamports returned to the owner
+    pub timestamp: i64, // Batch timestamp
+}
+
+#[event]
+pub struct PoolFunded {
+    pub amount: u64,    // Lamports deposited
+    pub balance: u64,   // Pool balance after deposit
+    pub timestamp: i64, // Deposit timestamp
+}
+
+#[event]
+pub struct PoolWithdrawn {
+    pub amount: u64,    // Lamports withdrawn
+    pub balance: u64,   // Pool balance after withdrawal
+    pub timestamp: i64, // Withdrawal timestamp
+}
+
+#[event]
+pub struct SwitchRevealed {
+    pub switch: Pubkey, // Switch account address
+    pub keeper: Pubkey, // Keeper that performed the reveal
+    pub reward: u64,    // Lamports paid to the keeper
+    pub timestamp: i64, // Reveal timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct RewardEscrowed {
+    pub switch: Pubkey,     // Switch account address
+    pub amount: u64,        // Lamports added to the escrow
+    pub keeper_reward: u64, // Escrowed total after the deposit
+    pub timestamp: i64,     // Deposit timestamp
+    pub seq: u64,           // Switch sequence number after this change
+}
+
+#[event]
+pub struct RewardReclaimed {
+    pub switch: Pubkey, // Switch account address
+    pub owner: Pubkey,  // Owner receiving the escrow
+    pub amount: u64,    // Lamports returned
+    pub timestamp: i64, // Reclaim timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct TemplateCreated {
+    pub template: Pubkey, // Template account address
+    pub owner: Pubkey,    // Owner public key
+    pub template_id: u64, // Template ID
+    pub timestamp: i64,   // Creation timestamp
+}
+
+#[event]
+pub struct OwnerDefaultsUpdated {
+    pub owner: Pubkey,         // Owner public key
+    pub ping_interval: i64,    // Default ping interval
+    pub grace_period: i64,     // Default grace period
+    pub warn_threshold: i64,   // Default warning lead time
+    pub encryption_scheme: u8, // Default encryption scheme
+    pub timestamp: i64,        // Update timestamp
+}
+
+#[event]
+pub struct IntervalUpdated {
+    pub switch: Pubkey,    // Switch account address
+    pub old_interval: i64, // Previous ping interval
+    pub new_interval: i64, // New ping interval
+    pub timer_reset: bool, // Whether last_ping was moved to now
+    pub timestamp: i64,    // Update timestamp
+    pub seq: u64,          // Switch sequence number after this change
+}
+
+#[event]
+pub struct TimePrepaid {
+    pub switch: Pubkey,   // Switch account address
+    pub added: i64,       // Seconds added by this prepayment
+    pub banked_time: i64, // Total banked seconds after prepayment
+    pub timestamp: i64,   // Prepayment timestamp
+    pub seq: u64,         // Switch sequence number after this change
+}
+
+#[event]
+pub struct SwitchUpdated {
+    pub switch: Pubkey, // Switch account address
+    pub actor: Pubkey,  // Signer that changed the settings
+    pub timestamp: i64, // Update timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct ClaimRejected {
+    pub switch: Pubkey,  // Switch account address
+    pub claimer: Pubkey, // Rejected claimer
+    pub attempts: u8,    // Failed attempts since the last success or lockout
+    pub timestamp: i64,  // Attempt timestamp
+    pub seq: u64,        // Switch sequence number after this change
+}
+
+#[event]
+pub struct OwnershipTransferProposed {
+    pub switch: Pubkey,        // Switch account address
+    pub owner: Pubkey,         // Current owner
+    pub pending_owner: Pubkey, // Proposed new owner
+    pub timestamp: i64,        // Proposal timestamp
+    pub seq: u64,              // Switch sequence number after this change
+}
+
+#[event]
+pub struct OwnershipTransferred {
+    pub switch: Pubkey,         // Switch account address
+    pub previous_owner: Pubkey, // Owner before the transfer
+    pub new_owner: Pubkey,      // Owner after the transfer
+    pub timestamp: i64,         // Transfer timestamp
+    pub seq: u64,               // Switch sequence number after this change
+}
+
+#[event]
+pub struct OwnershipTransferCancelled {
+    pub switch: Pubkey,        // Switch account address
+    pub owner: Pubkey,         // Owner that cancelled
+    pub pending_owner: Pubkey, // Proposed owner that was cleared
+    pub timestamp: i64,        // Cancellation timestamp
+    pub seq: u64,              // Switch sequence number after this change
+}
+
+#[event]
+pub struct HashFrozen {
+    pub switch: Pubkey, // Switch account address
+    pub hash: [u8; 32], // Hash of the data at settlement
+    pub timestamp: i64, // Freeze timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct DelegateAdded {
+    pub switch: Pubkey,   // Switch account address
+    pub delegate: Pubkey, // Added delegate
+    pub timestamp: i64,   // Change timestamp
+    pub seq: u64,         // Switch sequence number after this change
+}
+
+#[event]
+pub struct DelegateRemoved {
+    pub switch: Pubkey,   // Switch account address
+    pub delegate: Pubkey, // Removed delegate
+    pub timestamp: i64,   // Change timestamp
+    pub seq: u64,         // Switch sequence number after this change
+}
+
+#[event]
+pub struct OracleConditionMet {
+    pub switch: Pubkey, // Switch account address
+    pub oracle: Pubkey, // Oracle account read
+    pub value: i64,     // Oracle value observed
+    pub threshold: i64, // Threshold the value met
+    pub timestamp: i64, // Check timestamp
+}
+
+#[event]
+pub struct DataWiped {
+    pub switch: Pubkey,   // Switch account address
+    pub bytes_wiped: u32, // Account bytes overwritten with zeros
+    pub timestamp: i64,   // Wipe timestamp
+    pub seq: u64,         // Switch sequence number after this change
+}
+
+#[event]
+pub struct CompanionLinked {
+    pub switch: Pubkey,            // Switch account address
+    pub companion: Option<Pubkey>, // Linked companion switch, None when unlinked
+    pub timestamp: i64,            // Change timestamp
+    pub seq: u64,                  // Switch sequence number after this change
+}
+
+#[event]
+pub struct RevealPending {
+    pub switch: Pubkey, // Switch account address
+    pub reveal_at: i64, // Time the reveal delay elapses
+    pub timestamp: i64, // Observation timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct DataUpdated {
+    pub switch: Pubkey,      // Switch account address
+    pub data_length: u16,    // New data length in bytes
+    pub data_hash: [u8; 32], // Hash of the new data
+    pub timestamp: i64,      // Update timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct SnapshotTaken {
+    pub switch: Pubkey,      // Switch account address
+    pub snapshot: Pubkey,    // Snapshot account address
+    pub index: u32,          // Snapshot index
+    pub data_hash: [u8; 32], // Data hash captured
+    pub timestamp: i64,      // Snapshot timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct ExternalIdChanged {
     pub switch: Pubkey,       // Switch account address
-    pub owner: Pubkey,        // Owner public key
-    pub switch_id: u64,       // Unique switch ID
-    pub ping_interval: i64,   // Ping interval in seconds
-    pub expiration_time: i64, // Initial expiration timestamp
-    pub timestamp: i64,       // Creation timestamp
+    pub old_external_id: u64, // Previous bookkeeping ID
+    pub external_id: u64,     // New bookkeeping ID
+    pub timestamp: i64,       // Change timestamp
+    pub seq: u64,             // Switch sequence number after this change
 }
 
 #[event]
-pub struct SwitchPinged {
-    pub owner: Pubkey,           // Owner public key
-    pub switch_key: Pubkey,      // Switch account address
-    pub next_required_ping: i64, // Next required ping timestamp
-    pub timestamp: i64,          // Ping timestamp
+pub struct ClaimConfirmed {
+    pub switch: Pubkey,    // Switch account address
+    pub confirmer: Pubkey, // Confirming beneficiary
+    pub confirmations: u8, // Distinct confirmations so far
+    pub timestamp: i64,    // Confirmation timestamp
+    pub seq: u64,          // Switch sequence number after this change
 }
 
 #[event]
-pub struct SwitchExpired {
+pub struct DataBurned {
+    pub switch: Pubkey,      // Switch account address
+    pub bytes_burned: u16,   // Length of the destroyed data
+    pub data_hash: [u8; 32], // Hash of the destroyed data
+    pub timestamp: i64,      // Burn timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct RoleGranted {
+    pub switch: Pubkey,  // Switch account address
+    pub key: Pubkey,     // Key holding the role
+    pub permissions: u8, // Permission bits now held
+    pub timestamp: i64,  // Grant timestamp
+    pub seq: u64,        // Switch sequence number after this change
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub switch: Pubkey, // Switch account address
+    pub key: Pubkey,    // Key that lost its role
+    pub timestamp: i64, // Revocation timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct GlobalPauseToggled {
+    pub admin: Pubkey,  // Admin who toggled the pause
+    pub paused: bool,   // Whether state changes are now halted
+    pub timestamp: i64, // Toggle timestamp
+}
+
+#[event]
+pub struct ReceiptMinted {
+    pub switch: Pubkey,          // Switch account address
+    pub beneficiary: Pubkey,     // Claimer that received the receipt
+    pub mint: Pubkey,            // Receipt mint
+    pub receipt_account: Pubkey, // Token account holding the frozen receipt
+    pub timestamp: i64,          // Mint timestamp
+    pub seq: u64,                // Switch sequence number after this change
+}
+
+#[event]
+pub struct AttestationVerified {
+    pub switch: Pubkey,      // Switch account address
+    pub attestation: Pubkey, // Attestation account that was checked
+    pub attestor: Pubkey,    // Attestor that published it
+    pub claimer: Pubkey,     // Claimer the data was released to
+    pub timestamp: i64,      // Verification timestamp
+    pub seq: u64,            // Switch sequence number after this change
+}
+
+#[event]
+pub struct TokenBeneficiaryReassigned {
     pub switch: Pubkey,          // Switch account address
-    pub timestamp: i64,          // Expiration timestamp
+    pub old_beneficiary: Pubkey, // Previous token recipient, default if unset
+    pub new_beneficiary: Pubkey, // New token recipient
+    pub timestamp: i64,          // Change timestamp
+    pub seq: u64,                // Switch sequence number after this change
+}
+
+#[event]
+pub struct SwitchArmed {
+    pub switch: Pubkey,       // Switch account address
+    pub owner: Pubkey,        // Owner who armed it
+    pub expiration_time: i64, // Deadline now in force
+    pub timestamp: i64,       // Arming timestamp
+    pub seq: u64,             // Switch sequence number after this change
+}
+
+#[event]
+pub struct SwitchImported {
+    pub switch: Pubkey, // New switch account address
+    pub owner: Pubkey,  // Owner who imported it
+    pub switch_id: u64, // Seed ID of the new switch
+    pub version: u8,    // Export format version that was read
+    pub fee: u64,       // Creation fee charged, in lamports
+    pub timestamp: i64, // Import timestamp
+    pub seq: u64,       // Switch sequence number after import
+}
+
+#[event]
+pub struct IntervalLocked {
+    pub switch: Pubkey,     // Switch account address
+    pub ping_interval: i64, // Base interval fixed from now on
+    pub timestamp: i64,     // Lock timestamp
+    pub seq: u64,           // Switch sequence number after this change
+}
+
+#[event]
+pub struct FailoverActivated {
+    pub switch: Pubkey,               // Switch account address
+    pub failover_beneficiary: Pubkey, // Failover that claimed in place of the beneficiary
+    pub timestamp: i64,               // Activation timestamp
+    pub seq: u64,                     // Switch sequence number after this change
+}
+
+#[event]
+pub struct HubPinged {
+    pub hub: Pubkey,    // Hub account address
+    pub owner: Pubkey,  // Hub owner
+    pub synced: u32,    // Switches that received the heartbeat in the same transaction
+    pub timestamp: i64, // Heartbeat timestamp
+}
+
+#[event]
+pub struct HubHeartbeatApplied {
+    pub switch: Pubkey, // Switch account address
+    pub hub: Pubkey,    // Hub the heartbeat came from
+    pub heartbeat: i64, // Heartbeat time now counting as a ping
+    pub timestamp: i64, // Application timestamp
+    pub seq: u64,       // Switch sequence number after this change
+}
+
+#[event]
+pub struct MaintenanceStarted {
+    pub switch: Pubkey,       // Switch account address
+    pub duration: i64,        // Length of the window in seconds
+    pub until: i64,           // When the window ends
+    pub expiration_time: i64, // Deadline including the extension
+    pub timestamp: i64,       // Start timestamp
+    pub seq: u64,             // Switch sequence number after this change
+}
+
+#[event]
+pub struct MaintenanceEnded {
+    pub switch: Pubkey, // Switch account address
+    pub unused: i64,    // Seconds given back by ending early, 0 once the window elapsed
+    pub timestamp: i64, // End timestamp
+    pub seq: u64,       // Switch sequence number after this change
 }
 
 // ===== Error Codes ===== //
@@ -271,4 +10116,532 @@ pub enum ErrorCode {
     NotExpired,
     #[msg("Invalid timestamp detected")]
     InvalidTimestamp,
-}
\ No newline at end of file
+    #[msg("Ping counter must be greater than the last recorded counter")]
+    InvalidCounter,
+    #[msg("No callback program is configured for this switch")]
+    NoCallbackProgram,
+    #[msg("Callback program does not match the configured program")]
+    InvalidCallbackProgram,
+    #[msg("Callback has already been triggered")]
+    CallbackAlreadyTriggered,
+    #[msg("Owner has reached the maximum number of switches")]
+    TooManySwitches,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Too many accounts in batch")]
+    BatchTooLarge,
+    #[msg("Session duration must be between 1 second and 30 days")]
+    InvalidSessionDuration,
+    #[msg("No active session key")]
+    NoActiveSession,
+    #[msg("Switch is inactive")]
+    SwitchInactive,
+    #[msg("Switch must be deactivated first")]
+    SwitchActive,
+    #[msg("Schedule thresholds must be positive and strictly increasing")]
+    InvalidSchedule,
+    #[msg("Claims are locked after too many failed attempts")]
+    ClaimLocked,
+    #[msg("Encrypted data has low entropy and may be plaintext (heuristic, can misfire on short or encoded ciphertext)")]
+    LowEntropyData,
+    #[msg("Custody log is full")]
+    LogFull,
+    #[msg("This switch has a custody log that must be supplied")]
+    LogRequired,
+    #[msg("Reward pool cannot cover this payout")]
+    PoolExhausted,
+    #[msg("Keeper reward exceeds the per-reveal cap")]
+    RewardTooLarge,
+    #[msg("Switch has already been revealed")]
+    AlreadyRevealed,
+    #[msg("Grace period must be between 0 and 30 days")]
+    InvalidGracePeriod,
+    #[msg("Prepayment must add time and stay within the one-year cap")]
+    InvalidPrepayment,
+    #[msg("New owner must differ from the current owner")]
+    InvalidNewOwner,
+    #[msg("No ownership transfer is pending")]
+    NoPendingTransfer,
+    #[msg("Encrypted data changed after the switch expired")]
+    DataTampered,
+    #[msg("Delegate limit exceeds the supported capacity")]
+    InvalidMaxDelegates,
+    #[msg("Switch already has the maximum number of delegates")]
+    TooManyDelegates,
+    #[msg("Key is already a delegate")]
+    DelegateExists,
+    #[msg("Key is not a delegate")]
+    DelegateNotFound,
+    #[msg("The owner cannot be a delegate")]
+    InvalidDelegate,
+    #[msg("Claim must be co-signed by the switch witness")]
+    WitnessRequired,
+    #[msg("Switch has no oracle configured")]
+    NoOracle,
+    #[msg("Oracle account does not match the switch")]
+    InvalidOracle,
+    #[msg("Oracle account data is too short")]
+    InvalidOracleData,
+    #[msg("Warning threshold must be non-negative and below the ping interval")]
+    InvalidWarnThreshold,
+    #[msg("No keeper reward is escrowed on this switch")]
+    NoEscrowedReward,
+    #[msg("Withdrawal would leave the account below rent exemption")]
+    RentExemptionViolated,
+    #[msg("Beneficiary segments must be in bounds, non-empty and non-overlapping")]
+    InvalidSegment,
+    #[msg("Clock tolerance must be between 0 and 5 minutes")]
+    InvalidClockTolerance,
+    #[msg("A switch cannot be its own companion")]
+    InvalidCompanion,
+    #[msg("The linked companion switch must be supplied")]
+    CompanionRequired,
+    #[msg("The companion switch has not expired yet")]
+    CompanionNotExpired,
+    #[msg("Owner, registry and stats accounts are required to auto-close on claim")]
+    AutoCloseAccountsRequired,
+    #[msg("Owner defaults must be supplied when the ping interval is 0")]
+    DefaultsRequired,
+    #[msg("Switches must be distinct and passed in ascending address order")]
+    InvalidSwitchOrder,
+    #[msg("Combined data does not fit in the return data limit")]
+    CombinedTooLarge,
+    #[msg("Switches with a witness or companion must be claimed individually")]
+    CombinedClaimUnsupported,
+    #[msg("Unknown expiry action")]
+    InvalidExpiryAction,
+    #[msg("This switch declares a different expiry action")]
+    WrongExpiryAction,
+    #[msg("Proxy deadline must be in the future")]
+    InvalidProxyDeadline,
+    #[msg("No proxy is authorized")]
+    NoProxy,
+    #[msg("Another settlement of this switch is in progress")]
+    SettlementInProgress,
+    #[msg("Reveal delay must be between 0 and 30 days")]
+    InvalidRevealDelay,
+    #[msg("Reward decay window must be between 0 and 30 days")]
+    InvalidDecayWindow,
+    #[msg("Storage reference does not match the storage kind")]
+    InvalidStorageRef,
+    #[msg("Creation fee exceeds the maximum")]
+    InvalidCreationFee,
+    #[msg("Treasury account does not match the config")]
+    InvalidTreasury,
+    #[msg("Owner cannot cover the creation fee")]
+    InsufficientFunds,
+    #[msg("Switch has already been pinged")]
+    AlreadyActivated,
+    #[msg("This switch requires the new data hash on every update")]
+    HashRequired,
+    #[msg("Maximum data size must not exceed the inline data capacity")]
+    InvalidMaxDataSize,
+    #[msg("Close cooldown must be between 0 and 30 days")]
+    InvalidCloseCooldown,
+    #[msg("The switch was deactivated too recently to be closed")]
+    CloseCooldownActive,
+    #[msg("Log level must be minimal (0), normal (1) or verbose (2)")]
+    InvalidLogLevel,
+    #[msg("Beneficiary encryption key must not be all zeros")]
+    InvalidBeneficiaryPubkey,
+    #[msg("Stages must be set first, with increasing delays and lengths within the data")]
+    InvalidStages,
+    #[msg("No disclosure stage has unlocked any data yet")]
+    StageLocked,
+    #[msg("Not enough beneficiaries have confirmed the claim for this sensitivity level")]
+    ThresholdNotMet,
+    #[msg("This beneficiary has already confirmed the claim")]
+    AlreadyConfirmed,
+    #[msg("Sensitivity level is out of range")]
+    InvalidSensitivity,
+    #[msg(
+        "Required confirmations must not decrease with sensitivity or exceed the beneficiary count"
+    )]
+    InvalidConfirmationPolicy,
+    #[msg("The switch's data has been burned")]
+    DataBurned,
+    #[msg("Roles cannot be granted to the owner or the default key")]
+    InvalidRole,
+    #[msg("Permissions must be non-empty and use only known bits")]
+    InvalidPermissions,
+    #[msg("All role slots are in use")]
+    TooManyRoles,
+    #[msg("Key holds no role on this switch")]
+    RoleNotFound,
+    #[msg("Switches can only be cancelled shortly after creation and before the first ping")]
+    CancelWindowClosed,
+    #[msg("Ping memo must not be empty")]
+    EmptyMemo,
+    #[msg("Max lifetime must exceed the ping interval, end in the future, and never grow")]
+    InvalidMaxLifetime,
+    #[msg("Beneficiary notice must be non-negative and shorter than the ping interval")]
+    InvalidBeneficiaryNotice,
+    #[msg("The program is paused by the admin")]
+    GloballyPaused,
+    #[msg("Receipt minting needs the receipt mint, receipt account and token program")]
+    ReceiptAccountsRequired,
+    #[msg("Receipt mint must be the configured zero-decimal mint controlled by the switch")]
+    InvalidReceiptMint,
+    #[msg("Receipt account must be the claimer's token account for the receipt mint")]
+    InvalidReceiptAccount,
+    #[msg("A published attestation is required to claim")]
+    AttestationRequired,
+    #[msg("Attestation does not match this switch or its attestor")]
+    AttestationMismatch,
+    #[msg("An attestor must be set when attestation is required")]
+    AttestorRequired,
+    #[msg("Jurisdiction must be printable ASCII padded with zeros")]
+    InvalidJurisdiction,
+    #[msg("Token beneficiary must be a real key")]
+    InvalidTokenBeneficiary,
+    #[msg("Mode must be dead-man or keepalive")]
+    InvalidMode,
+    #[msg("Only keepalive switches can be armed")]
+    NotKeepalive,
+    #[msg("Switch is already armed")]
+    AlreadyArmed,
+    #[msg("Too many delegate pings in a row; the owner must ping")]
+    OwnerPingRequired,
+    #[msg("Blob is not a switch state export")]
+    InvalidExport,
+    #[msg("Unsupported state export version")]
+    UnsupportedExportVersion,
+    #[msg("State export is too large to import")]
+    ExportTooLarge,
+    #[msg("Import chunks must be staged in order")]
+    ImportOutOfOrder,
+    #[msg("Window count must fit the ping history and the window must be a valid interval")]
+    InvalidPingWindow,
+    #[msg("Recent pings do not satisfy the window policy")]
+    PingWindowNotMet,
+    #[msg("Ping interval is locked")]
+    IntervalLocked,
+    #[msg("Failover must differ from the beneficiary, with a delay of at most a year")]
+    InvalidFailover,
+    #[msg("The owner's heartbeat hub must be provided")]
+    HubRequired,
+    #[msg("Hub heartbeat is not newer or the switch is not linked to this hub")]
+    HubHeartbeatNotApplicable,
+    #[msg("A maintenance window is already open")]
+    MaintenanceActive,
+    #[msg("Switch is not in maintenance")]
+    NotInMaintenance,
+    #[msg("Maintenance must be positive and within the per-ping cap")]
+    InvalidMaintenanceDuration,
+    #[msg("No beneficiary root has been committed")]
+    BeneficiaryRootNotSet,
+    #[msg("Merkle proof or root is malformed")]
+    InvalidProof,
+    #[msg("Claim fee recipient is missing or does not match the switch")]
+    InvalidFeeRecipient,
+    #[msg("Confirmation interval must be between 0 and 30 days")]
+    InvalidConfirmInterval,
+    #[msg("This switch has a backup that must be supplied")]
+    BackupRequired,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh active switch with every optional feature off
+    fn test_switch() -> DeadManSwitch {
+        let mut switch =
+            DeadManSwitch::try_deserialize_unchecked(&mut &vec![0u8; DeadManSwitch::SPACE][..])
+                .unwrap();
+        switch.active = true;
+        switch.ping_interval = 86400;
+        switch.created_at = 1_000_000;
+        switch.last_ping = 1_000_000;
+        switch
+    }
+
+    #[test]
+    fn claim_data_releases_only_unlocked_prefix() {
+        let mut switch = test_switch();
+        switch.data_length = 64;
+        switch.encrypted_data[..64].copy_from_slice(&[7u8; 64]);
+        switch.stages[0] = DisclosureStage {
+            delay: 0,
+            length: 16,
+        };
+        switch.stages[1] = DisclosureStage {
+            delay: 3600,
+            length: 64,
+        };
+        let reveal_at = reveal_time(&switch, 0).unwrap();
+
+        assert!(released_data(&switch, reveal_at - 1).is_err());
+        assert_eq!(released_data(&switch, reveal_at).unwrap().len(), 16);
+        assert_eq!(released_data(&switch, reveal_at + 3599).unwrap().len(), 16);
+        assert_eq!(released_data(&switch, reveal_at + 3600).unwrap().len(), 64);
+    }
+
+    #[test]
+    fn unstaged_switch_releases_everything() {
+        let mut switch = test_switch();
+        switch.data_length = 32;
+        let reveal_at = reveal_time(&switch, 0).unwrap();
+
+        assert_eq!(released_data(&switch, reveal_at).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn seq_overflow_is_arithmetic_overflow() {
+        let mut switch = test_switch();
+        switch.seq = u64::MAX;
+
+        assert_eq!(
+            bump_seq(&mut switch).unwrap_err(),
+            ErrorCode::ArithmeticOverflow.into()
+        );
+    }
+
+    #[test]
+    fn id_overflow_is_arithmetic_overflow() {
+        let mut registry = OwnerRegistry {
+            owner: Pubkey::new_unique(),
+            count: 1,
+            bump: 255,
+            next_id: 1,
+        };
+
+        assert_eq!(
+            reserve_id(&mut registry, u64::MAX).unwrap_err(),
+            ErrorCode::ArithmeticOverflow.into()
+        );
+    }
+
+    #[test]
+    fn stranger_update_is_unauthorized() {
+        let mut switch = test_switch();
+        switch.owner = Pubkey::new_unique();
+
+        assert_eq!(
+            replace_data(
+                &mut switch,
+                Pubkey::new_unique(),
+                &[1u8; 32],
+                None,
+                1_000_000
+            )
+            .unwrap_err(),
+            ErrorCode::Unauthorized.into()
+        );
+    }
+
+    #[test]
+    fn settling_switch_refuses_late_pings() {
+        let mut switch = test_switch();
+        switch.auto_extend_on_late = true;
+        let late = reveal_time(&switch, 0).unwrap() + 1;
+        assert!(accepts_late_ping(&switch, late));
+
+        // What a callback re-entering the program sees mid-CPI
+        switch.settling = true;
+        assert!(!accepts_late_ping(&switch, late));
+    }
+
+    /// High-entropy bytes that pass validate_data
+    fn test_data(len: usize) -> Vec<u8> {
+        (0..=u8::MAX)
+            .flat_map(|i| hash(&[i]).to_bytes())
+            .take(len)
+            .collect()
+    }
+
+    fn create(ping_interval: i64) -> Result<DeadManSwitch> {
+        let mut switch = test_switch();
+        init_switch(
+            &mut switch,
+            Pubkey::new_unique(),
+            1,
+            ping_interval,
+            &test_data(64),
+            255,
+            1_000_000,
+        )?;
+        Ok(switch)
+    }
+
+    #[test]
+    fn creation_accepts_boundary_intervals() {
+        for ping_interval in [MIN_PING_INTERVAL, MAX_PING_INTERVAL] {
+            let switch = create(ping_interval).unwrap();
+            assert_eq!(
+                expiration_time(&switch, switch.created_at),
+                Some(switch.created_at + ping_interval)
+            );
+        }
+    }
+
+    #[test]
+    fn creation_rejects_out_of_range_intervals() {
+        for ping_interval in [
+            i64::MIN,
+            -1,
+            0,
+            MIN_PING_INTERVAL - 1,
+            MAX_PING_INTERVAL + 1,
+        ] {
+            assert_eq!(
+                create(ping_interval).unwrap_err(),
+                ErrorCode::InvalidInterval.into()
+            );
+        }
+    }
+
+    #[test]
+    fn import_rejects_out_of_range_slots() {
+        let mut state = create(86400).unwrap();
+        state.delegate_count = MAX_DELEGATES as u8 + 1;
+        assert_eq!(
+            sanitize_import(&mut state, 2_000_000).unwrap_err(),
+            ErrorCode::InvalidExport.into()
+        );
+
+        let mut state = create(86400).unwrap();
+        state.ping_history_head = PING_HISTORY_SIZE as u8;
+        assert_eq!(
+            sanitize_import(&mut state, 2_000_000).unwrap_err(),
+            ErrorCode::InvalidExport.into()
+        );
+
+        let mut state = create(86400).unwrap();
+        state.data_hash = [0u8; 32];
+        assert_eq!(
+            sanitize_import(&mut state, 2_000_000).unwrap_err(),
+            ErrorCode::InvalidExport.into()
+        );
+    }
+
+    #[test]
+    fn import_caps_timestamps_and_resets_settlement() {
+        let mut state = create(86400).unwrap();
+        state.last_ping = i64::MAX;
+        state.created_at = i64::MAX;
+        state.armed_at = i64::MAX;
+        state.expired = true;
+        state.callback_triggered = true;
+        state.frozen_hash = Some(state.data_hash);
+        state.claim_confirmations = 1;
+        state.phase = PHASE_DEAD;
+
+        sanitize_import(&mut state, 2_000_000).unwrap();
+        assert_eq!(state.last_ping, 2_000_000);
+        assert_eq!(state.created_at, 2_000_000);
+        assert_eq!(state.armed_at, 2_000_000);
+        assert!(!state.expired && !state.callback_triggered);
+        assert_eq!(state.frozen_hash, None);
+        assert_eq!(state.claim_confirmations, 0);
+        assert_eq!(state.phase, PHASE_ALIVE);
+    }
+
+    #[test]
+    fn status_offset_points_at_status_byte() {
+        let mut switch = test_switch();
+        switch.status_byte = 0xAB;
+        let mut data = Vec::new();
+        switch.try_serialize(&mut data).unwrap();
+
+        assert_eq!(data.len(), DeadManSwitch::SPACE);
+        assert_eq!(data[DeadManSwitch::STATUS_OFFSET], 0xAB);
+    }
+
+    /// Pings `authority` MAX_DELEGATE_PINGS times, then once more
+    fn ping_past_delegate_limit(switch: &mut DeadManSwitch, authority: Pubkey) -> Result<()> {
+        for _ in 0..MAX_DELEGATE_PINGS {
+            count_delegate_ping(switch, authority)?;
+        }
+        count_delegate_ping(switch, authority)
+    }
+
+    #[test]
+    fn delegate_pings_count_toward_the_limit() {
+        let mut switch = test_switch();
+        let delegate = Pubkey::new_unique();
+        switch.delegates[0] = delegate;
+        switch.delegate_count = 1;
+
+        assert_eq!(
+            ping_past_delegate_limit(&mut switch, delegate).unwrap_err(),
+            ErrorCode::OwnerPingRequired.into()
+        );
+        count_delegate_ping(&mut switch, switch.owner).unwrap();
+        assert_eq!(switch.delegate_pings_since_owner, 0);
+    }
+
+    #[test]
+    fn role_pings_count_toward_the_limit() {
+        let mut switch = test_switch();
+        let key = Pubkey::new_unique();
+        switch.roles[0] = RoleGrant {
+            key,
+            permissions: PERM_PING,
+        };
+
+        assert_eq!(
+            ping_past_delegate_limit(&mut switch, key).unwrap_err(),
+            ErrorCode::OwnerPingRequired.into()
+        );
+    }
+
+    #[test]
+    fn session_key_pings_are_not_counted() {
+        let mut switch = test_switch();
+        let key = Pubkey::new_unique();
+        switch.session_key = Some(key);
+        switch.session_expires_at = 2_000_000;
+
+        ping_past_delegate_limit(&mut switch, key).unwrap();
+        assert_eq!(switch.delegate_pings_since_owner, 0);
+    }
+
+    #[test]
+    fn proxy_pings_are_not_counted() {
+        let mut switch = test_switch();
+        let key = Pubkey::new_unique();
+        switch.proxy = Some(key);
+        switch.proxy_max_until = 2_000_000;
+
+        ping_past_delegate_limit(&mut switch, key).unwrap();
+        assert_eq!(switch.delegate_pings_since_owner, 0);
+    }
+
+    #[test]
+    fn degenerate_interval_is_invalid_timestamp() {
+        for ping_interval in [0, -1] {
+            let mut switch = test_switch();
+            switch.ping_interval = ping_interval;
+
+            assert_eq!(
+                check_first_expiration(&switch, switch.created_at).unwrap_err(),
+                ErrorCode::InvalidTimestamp.into()
+            );
+        }
+        assert!(check_first_expiration(&test_switch(), 1_000_000).is_ok());
+    }
+
+    #[test]
+    fn settling_switch_refuses_settlement() {
+        let mut switch = test_switch();
+        assert!(require_not_settling(&switch).is_ok());
+
+        switch.settling = true;
+        assert_eq!(
+            require_not_settling(&switch).unwrap_err(),
+            ErrorCode::SettlementInProgress.into()
+        );
+    }
+
+    #[test]
+    fn finished_callback_clears_settling() {
+        let mut switch = test_switch();
+        switch.settling = true;
+
+        finish_callback(&mut switch);
+        assert!(!switch.settling);
+        assert!(switch.callback_triggered && switch.expired);
+        assert!(require_not_settling(&switch).is_ok());
+    }
+}